@@ -0,0 +1,235 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Parses the arguments of attributes like `#[proconio::main(multi)]`.
+//!
+//! Each argument is either a bare name (`multi`) or a name followed by a value (`name = value`).
+//! Values are parsed as expressions so that the attribute can accept literals, paths and calls.
+
+use proc_macro2::{Span as Span2, TokenStream as TokenStream2};
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Expr, ExprLit, Ident, Lit, Stmt, Token};
+
+pub struct Arg {
+    pub name: Ident,
+    pub value: Option<Expr>,
+}
+
+impl Arg {
+    pub fn span(&self) -> (Span2, Span2) {
+        match &self.value {
+            Some(value) => (self.name.span(), value.span()),
+            None => (self.name.span(), self.name.span()),
+        }
+    }
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = Ident::parse_any(input)?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        Ok(Arg { name, value })
+    }
+}
+
+struct Args(Punctuated<Arg, Token![,]>);
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Punctuated::parse_terminated(input).map(Args)
+    }
+}
+
+/// Parses the attribute arguments.  On failure, returns a `compile_error!` statement pointing the
+/// erroneous token.
+pub fn parse(attr: TokenStream2) -> Result<Vec<Arg>, Vec<Stmt>> {
+    match syn::parse2::<Args>(attr) {
+        Ok(Args(args)) => Ok(args.into_iter().collect()),
+        Err(e) => {
+            let message = e.to_string();
+            Err(vec![crate::compile_error_at(
+                quote!(#message),
+                e.span(),
+                e.span(),
+            )])
+        }
+    }
+}
+
+/// Creates a `compile_error!` statement pointing the argument.
+pub fn error_at(arg: &Arg, message: &str) -> Stmt {
+    let (start, end) = arg.span();
+    crate::compile_error_at(quote!(#message), start, end)
+}
+
+/// Gets the value of the argument, or reports an error if the value is missing.
+pub fn value(arg: &Arg) -> Result<&Expr, Vec<Stmt>> {
+    arg.value.as_ref().ok_or_else(|| {
+        vec![error_at(
+            arg,
+            &format!("`{}` requires a value like `{} = ...`.", arg.name, arg.name),
+        )]
+    })
+}
+
+/// Reports an error if the argument has a value.
+pub fn no_value(arg: &Arg) -> Result<(), Vec<Stmt>> {
+    match arg.value {
+        Some(_) => Err(vec![error_at(
+            arg,
+            &format!("`{}` does not take a value.", arg.name),
+        )]),
+        None => Ok(()),
+    }
+}
+
+/// Stores the value to the slot, or reports an error if the option is already specified.
+pub fn set_once<T>(slot: &mut Option<T>, arg: &Arg, value: T) -> Result<(), Vec<Stmt>> {
+    if slot.is_some() {
+        return Err(vec![error_at(
+            arg,
+            &format!("`{}` is specified more than once.", arg.name),
+        )]);
+    }
+
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Parses the value of `stack = ...` into an expression of type `usize`.
+///
+/// A string literal is parsed as a size with an optional unit like `"512MiB"`.  Any other
+/// expression is used as is, as the number of bytes.
+pub fn stack_size(arg: &Arg) -> Result<TokenStream2, Vec<Stmt>> {
+    let value = value(arg)?;
+    let lit = match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => lit,
+        _ => return Ok(quote!((#value) as usize)),
+    };
+
+    match parse_size(&lit.value()) {
+        Some(size) => Ok(quote!(#size)),
+        None => Err(vec![error_at(
+            arg,
+            concat!(
+                "invalid stack size; ",
+                "expected a number followed by an optional unit like \"512MiB\" ",
+                "(available units: B, KiB, MiB, GiB, KB, MB, GB).",
+            ),
+        )]),
+    }
+}
+
+/// Parses the value of `capacity = ...` into an expression of type `usize`.
+pub fn capacity(arg: &Arg) -> Result<TokenStream2, Vec<Stmt>> {
+    let value = value(arg)?;
+    let valid = match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit
+            .base10_parse::<usize>()
+            .map_or(false, |capacity| capacity > 0),
+        Expr::Lit(_) => false,
+        _ => true,
+    };
+
+    if !valid {
+        return Err(vec![error_at(
+            arg,
+            "invalid capacity; expected a positive number of bytes like `1 << 20`.",
+        )]);
+    }
+
+    Ok(quote!({
+        let capacity: usize = #value;
+        capacity
+    }))
+}
+
+/// Parses the value of `sink = ...` into an expression of type `proconio::out::Sink`.
+///
+/// The value is either `stdout`, `stderr` or `file(path)`.
+pub fn sink(arg: &Arg) -> Result<TokenStream2, Vec<Stmt>> {
+    let error = || {
+        vec![error_at(
+            arg,
+            "invalid sink; expected `stdout`, `stderr` or `file(\"path\")`.",
+        )]
+    };
+
+    match value(arg)? {
+        Expr::Path(path) => match path.path.get_ident().map(Ident::to_string).as_deref() {
+            Some("stdout") => Ok(quote!(::proconio::out::Sink::Stdout)),
+            Some("stderr") => Ok(quote!(::proconio::out::Sink::Stderr)),
+            _ => Err(error()),
+        },
+        Expr::Call(call) => {
+            let is_file = match &*call.func {
+                Expr::Path(func) => func.path.is_ident("file"),
+                _ => false,
+            };
+            if !is_file || call.args.len() != 1 {
+                return Err(error());
+            }
+
+            let path = &call.args[0];
+            Ok(quote!(::proconio::out::Sink::__file(#path)))
+        }
+        _ => Err(error()),
+    }
+}
+
+/// Parses the value of `flush = ...` into an expression of type `proconio::out::Flush`.
+pub fn flush_policy(arg: &Arg) -> Result<TokenStream2, Vec<Stmt>> {
+    let policy = match value(arg)? {
+        Expr::Path(path) => path.path.get_ident().map(Ident::to_string),
+        _ => None,
+    };
+
+    match policy.as_deref() {
+        Some("auto") => Ok(quote!(::proconio::out::Flush::Auto)),
+        Some("line") => Ok(quote!(::proconio::out::Flush::Line)),
+        Some("manual") => Ok(quote!(::proconio::out::Flush::Manual)),
+        _ => Err(vec![error_at(
+            arg,
+            "invalid flush policy; expected `auto`, `line` or `manual`.",
+        )]),
+    }
+}
+
+fn parse_size(size: &str) -> Option<usize> {
+    let size = size.trim();
+    let digits = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(digits);
+    let number: usize = number.parse().ok()?;
+    let unit: usize = match unit.trim() {
+        "" | "B" => 1,
+        "K" | "KiB" => 1 << 10,
+        "M" | "MiB" => 1 << 20,
+        "G" | "GiB" => 1 << 30,
+        "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        _ => return None,
+    };
+
+    number.checked_mul(unit)
+}
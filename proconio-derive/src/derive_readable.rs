@@ -5,6 +5,7 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
 // distributed except according to those terms.
 
+use crate::field_attr::{self, FieldKind};
 use proc_macro::TokenStream;
 use proc_macro2::{Span as Span2, TokenStream as TokenStream2};
 use quote::quote;
@@ -12,9 +13,52 @@ use quote::ToTokens;
 use syn::parse_macro_input;
 use syn::parse_quote;
 use syn::spanned::Spanned;
-use syn::{Data, DataStruct, DeriveInput, Fields, Ident, Type};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    Attribute, Data, DataEnum, DataStruct, DeriveInput, Field, Fields, Generics, Ident, Lit, Meta,
+    MetaNameValue, Type, TypePath, WherePredicate,
+};
 
 pub fn main(attr: TokenStream, input: TokenStream) -> TokenStream {
+    if let Err(error) = check_no_attr(attr) {
+        return error;
+    }
+
+    let mut ast = parse_macro_input!(input as DeriveInput);
+    let paired = restore_markers(&mut ast) || has_attr(&ast, "derive_writable");
+
+    // derive actually Readable
+    let generics = impl_generics(&ast, readable_bounds, paired);
+    let derive = match derive_readable_impl(&ast, generics) {
+        Ok(derive) => derive,
+        Err(error) => return error,
+    };
+
+    // modify AST to use actual Readable::Output type
+    if let Err(error) = replace_type(&mut ast) {
+        return error;
+    }
+
+    // the replaced types require the type parameters to be Readable
+    add_bounds(&mut ast.generics, readable_bounds);
+
+    quote!(#ast #derive).into()
+}
+
+/// `#[derive(Readable)]`: the struct is left unchanged, and the fields are read by their own types
+/// or by the types given by `#[readable(as = ...)]`.
+pub fn derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    let mut generics = ast.generics.clone();
+    add_bounds(&mut generics, output_bounds);
+    match derive_readable_impl(&ast, generics) {
+        Ok(derive) => derive.into(),
+        Err(error) => error,
+    }
+}
+
+pub(crate) fn check_no_attr(attr: TokenStream) -> Result<(), TokenStream> {
     if !attr.is_empty() {
         let mut attr = attr.into_iter();
         let start = attr
@@ -33,61 +77,78 @@ pub fn main(attr: TokenStream, input: TokenStream) -> TokenStream {
             Span2::from(end),
         );
 
-        return compile_error.into_token_stream().into();
+        return Err(compile_error.into_token_stream().into());
     }
 
-    let mut ast = parse_macro_input!(input as DeriveInput);
+    Ok(())
+}
 
-    // derive actually Readable
-    let derive = match derive_readable_impl(&ast) {
-        Ok(derive) => derive,
-        Err(error) => return error,
-    };
+pub(crate) fn replace_type(ast: &mut DeriveInput) -> Result<(), TokenStream> {
+    let params = type_params(&ast.generics);
 
-    // modify AST to use actual Readable::Output type
-    if let Err(error) = replace_type(&mut ast) {
-        return error;
+    if let Data::Enum(data) = &mut ast.data {
+        for variant in &mut data.variants {
+            // `#[tag]` is only for this macro and is not a real attribute.
+            variant.attrs.retain(|attr| !attr.path.is_ident("tag"));
+        }
     }
 
-    quote!(#ast #derive).into()
-}
-
-fn replace_type(ast: &mut DeriveInput) -> Result<(), TokenStream> {
-    let data = get_data_mut(ast)?;
+    // `#[readable]` is left for `#[derive_writable]` if it is not expanded yet.
+    let keep_attrs = has_attr(ast, "derive_writable");
 
-    for field in data.fields.iter_mut() {
+    for field in fields_mut(ast) {
         let (start, end) = {
             let ty = field.ty.clone().into_token_stream();
             crate::get_span_range(ty.into())
         };
 
-        let new_ty: Type = {
-            let ty = field.ty.clone().into_token_stream();
-            parse_quote!(<#ty as ::proconio::source::Readable>::Output)
+        let readable = quote!(::proconio::source::Readable);
+        let new_ty = match field_attr::parse(field)? {
+            FieldKind::Readable => replaced_type(&field.ty, &params, readable, "Output"),
+            FieldKind::Len {
+                elem, reader: None, ..
+            } => {
+                let elem = replaced_type(&elem, &params, readable, "Output");
+                parse_quote!(::std::vec::Vec<#elem>)
+            }
+            // the type is written as the actual type
+            FieldKind::As(_)
+            | FieldKind::Len { .. }
+            | FieldKind::Kind(_)
+            | FieldKind::With(_)
+            | FieldKind::Default(_) => field.ty.clone(),
         };
 
         // Restore original spanning info
         let respanned = crate::set_span_range(new_ty, start, end);
         field.ty = respanned;
+
+        if !keep_attrs {
+            field
+                .attrs
+                .retain(|attr| !field_attr::is_readable_attr(attr));
+        }
     }
 
     Ok(())
 }
 
-fn derive_readable_impl(ast: &DeriveInput) -> Result<TokenStream2, TokenStream> {
+fn derive_readable_impl(
+    ast: &DeriveInput,
+    generics: Generics,
+) -> Result<TokenStream2, TokenStream> {
     let name = get_name(ast);
-    let fields = &get_data(ast)?.fields;
-
-    let field_info = field_info(fields);
-    let generate = generate(fields, &name, &field_info);
-    let reads = field_info.iter().map(|f| &f.read);
+    let read = read_value(ast, "Readable")?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    // `R` and `S` may be used by the type parameters of the struct.
     let res = quote! {
-        impl ::proconio::source::Readable for #name {
-            type Output = #name;
-            fn read<R: ::std::io::BufRead, S: ::proconio::source::Source<R>>(source: &mut S) -> #name {
-                #(#reads)*
-                #generate
+        impl #impl_generics ::proconio::source::Readable for #name #ty_generics #where_clause {
+            type Output = Self;
+            fn read<__R: ::std::io::BufRead, __S: ::proconio::source::Source<__R>>(
+                source: &mut __S,
+            ) -> Self {
+                #read
             }
         }
     };
@@ -95,11 +156,181 @@ fn derive_readable_impl(ast: &DeriveInput) -> Result<TokenStream2, TokenStream>
     Ok(res)
 }
 
-fn get_name(ast: &DeriveInput) -> Ident {
+/// Generates the expression reading the value from `source`.
+pub(crate) fn read_value(ast: &DeriveInput, trait_name: &str) -> Result<TokenStream2, TokenStream> {
+    let name = get_name(ast);
+    match &ast.data {
+        Data::Struct(data) => read_fields(&data.fields, quote!(#name)),
+        Data::Enum(data) => read_enum(&name, data),
+        Data::Union(_) => Err(error_at(
+            &format!("{trait_name} can only derivable for structs and enums."),
+            &ast.ident,
+        )),
+    }
+}
+
+pub(crate) fn readable_bounds(param: &Ident) -> Vec<WherePredicate> {
+    vec![parse_quote!(#param: ::proconio::source::Readable)]
+}
+
+/// A field of type parameter `T` is read by `T` itself, so it must be read as `T`.
+fn output_bounds(param: &Ident) -> Vec<WherePredicate> {
+    vec![parse_quote!(#param: ::proconio::source::Readable<Output = #param>)]
+}
+
+/// Adds the bounds to the where clause for each type parameter.
+pub(crate) fn add_bounds(generics: &mut Generics, bounds: fn(&Ident) -> Vec<WherePredicate>) {
+    let params = type_params(generics);
+    let where_clause = generics.make_where_clause();
+    for param in &params {
+        where_clause.predicates.extend(bounds(param));
+    }
+}
+
+/// Gets the generics of the impl, having `bounds` for each type parameter.
+///
+/// When `#[derive_readable]` and `#[derive_writable]` are both applied, the inner one adds its
+/// bounds to the struct after the outer one generated its impl, so both impls need the bounds of
+/// both.  Also, a field of type parameter `T` has type `T::Output` or `T::Value` depending on
+/// which is applied last, so they are required to be the same.
+pub(crate) fn impl_generics(
+    ast: &DeriveInput,
+    bounds: fn(&Ident) -> Vec<WherePredicate>,
+    paired: bool,
+) -> Generics {
+    let mut generics = ast.generics.clone();
+    if paired {
+        add_bounds(&mut generics, paired_bounds);
+    } else {
+        add_bounds(&mut generics, bounds);
+    }
+
+    generics
+}
+
+fn paired_bounds(param: &Ident) -> Vec<WherePredicate> {
+    let mut bounds = readable_bounds(param);
+    bounds.extend(crate::derive_writable::writable_bounds(param));
+    bounds.push(parse_quote! {
+        #param: ::proconio::output::Writable<
+            Value = <#param as ::proconio::source::Readable>::Output,
+        >
+    });
+
+    bounds
+}
+
+/// Whether the item has the attribute yet to be expanded.
+pub(crate) fn has_attr(ast: &DeriveInput, name: &str) -> bool {
+    ast.attrs.iter().any(|attr| {
+        attr.path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == name)
+    })
+}
+
+/// Reads the fields and constructs the value by `path`, which is the name of the struct or the
+/// path to the variant.
+fn read_fields(fields: &Fields, path: TokenStream2) -> Result<TokenStream2, TokenStream> {
+    let field_info = field_info(fields)?;
+    let generate = generate(fields, &path, &field_info);
+    let reads = field_info.iter().map(|f| &f.read);
+
+    Ok(quote! {
+        #(#reads)*
+        #generate
+    })
+}
+
+/// Reads the tag token first, and then reads the fields of the variant having the tag.
+fn read_enum(name: &Ident, data: &DataEnum) -> Result<TokenStream2, TokenStream> {
+    let tags = variant_tags(data)?;
+    let mut arms = Vec::new();
+    for (variant, tag) in data.variants.iter().zip(&tags) {
+        let ident = &variant.ident;
+        let read = read_fields(&variant.fields, quote!(#name::#ident))?;
+        arms.push(quote!(#tag => { #read }));
+    }
+
+    let expected = match &*tags {
+        [] => "nothing".to_string(),
+        [tag] => format!("`{tag}`"),
+        [init @ .., last] => {
+            let init: Vec<_> = init.iter().map(|tag| format!("`{tag}`")).collect();
+            format!("{} or `{last}`", init.join(", "))
+        }
+    };
+    let name_str = name.to_string();
+
+    Ok(quote! {
+        let tag = <::std::string::String as ::proconio::source::Readable>::read(source);
+        match tag.as_str() {
+            #(#arms)*
+            _ => panic!("unknown tag `{}` for `{}`; expected {}.", tag, #name_str, #expected),
+        }
+    })
+}
+
+/// Gets the tag of each variant: the value of `#[tag = ...]`, or its 1-based position if omitted.
+fn variant_tags(data: &DataEnum) -> Result<Vec<String>, TokenStream> {
+    let mut tags = Vec::new();
+
+    for (idx, variant) in data.variants.iter().enumerate() {
+        let mut tag = None;
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("tag"))
+        {
+            if tag.is_some() {
+                return Err(error_at("duplicate `tag` attribute.", attr));
+            }
+
+            tag = Some(parse_tag(attr)?);
+        }
+
+        let tag = tag.unwrap_or_else(|| (idx + 1).to_string());
+        if tags.contains(&tag) {
+            return Err(error_at(&format!("duplicate tag `{tag}`."), variant));
+        }
+
+        tags.push(tag);
+    }
+
+    Ok(tags)
+}
+
+fn parse_tag(attr: &Attribute) -> Result<String, TokenStream> {
+    match attr.parse_meta() {
+        Ok(Meta::NameValue(MetaNameValue {
+            lit: Lit::Int(lit), ..
+        })) => Ok(lit.base10_digits().to_string()),
+        Ok(Meta::NameValue(MetaNameValue {
+            lit: Lit::Str(lit), ..
+        })) => Ok(lit.value()),
+        _ => Err(error_at(
+            "invalid tag; expected an integer or a string like `#[tag = 1]` or `#[tag = \"add\"]`.",
+            attr,
+        )),
+    }
+}
+
+pub(crate) fn error_at<T: ToTokens>(message: &str, tokens: &T) -> TokenStream {
+    let (start, end) = crate::get_span_range(tokens.to_token_stream().into());
+    crate::compile_error_at(quote!(#message), Span2::from(start), Span2::from(end))
+        .into_token_stream()
+        .into()
+}
+
+pub(crate) fn get_name(ast: &DeriveInput) -> Ident {
     ast.ident.clone()
 }
 
-fn get_data(ast: &DeriveInput) -> Result<&DataStruct, TokenStream> {
+pub(crate) fn get_data<'a>(
+    ast: &'a DeriveInput,
+    trait_name: &str,
+) -> Result<&'a DataStruct, TokenStream> {
     let start = ast.span();
     let end = ast.ident.span();
 
@@ -107,86 +338,191 @@ fn get_data(ast: &DeriveInput) -> Result<&DataStruct, TokenStream> {
 
     match data {
         Data::Struct(data) => Ok(data),
-        _ => Err(crate::compile_error_at(
-            quote!("Readable can only derivable for structs."),
-            start,
-            end,
-        )
-        .into_token_stream()
-        .into()),
+        _ => Err(not_struct_error(trait_name, start, end)),
     }
 }
 
-fn get_data_mut(ast: &mut DeriveInput) -> Result<&mut DataStruct, TokenStream> {
+pub(crate) fn get_data_mut<'a>(
+    ast: &'a mut DeriveInput,
+    trait_name: &str,
+) -> Result<&'a mut DataStruct, TokenStream> {
     let start = ast.span();
     let end = ast.ident.span();
     let data = &mut ast.data;
 
     match data {
         Data::Struct(data) => Ok(data),
-        _ => Err(crate::compile_error_at(
-            quote!("Readable can only derivable for structs."),
-            start,
-            end,
-        )
+        _ => Err(not_struct_error(trait_name, start, end)),
+    }
+}
+
+fn not_struct_error(trait_name: &str, start: Span2, end: Span2) -> TokenStream {
+    let message = format!("{trait_name} can only derivable for structs.");
+    crate::compile_error_at(quote!(#message), start, end)
         .into_token_stream()
-        .into()),
+        .into()
+}
+
+/// Restores the marker type of each field.
+///
+/// `#[derive_readable]` and `#[derive_writable]` replace the type of each field with the actual
+/// type like `<Usize1 as Readable>::Output`.  When both are applied, the second one sees the
+/// replaced type, so it is unwrapped here to find the original marker type `Usize1`.  Returns
+/// whether any field is restored, that is, the other one is already applied.
+pub(crate) fn restore_markers(ast: &mut DeriveInput) -> bool {
+    let mut visitor = RestoreMarkers {
+        params: type_params(&ast.generics),
+        restored: false,
+    };
+    for field in fields_mut(ast) {
+        visitor.visit_type_mut(&mut field.ty);
     }
+
+    visitor.restored
 }
 
-struct FieldInfo {
-    ident: Ident,
-    read: TokenStream2,
+/// Restores the marker types appearing anywhere in the type, like `Vec<Usize1>` of
+/// `#[readable(len = n)]`.
+struct RestoreMarkers {
+    params: Vec<Ident>,
+    restored: bool,
 }
 
-fn field_info(fields: &Fields) -> Vec<FieldInfo> {
-    match fields {
-        Fields::Named(_) => field_named(fields),
-        Fields::Unnamed(_) => field_unnamed(fields),
-        Fields::Unit => Vec::new(),
+impl VisitMut for RestoreMarkers {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        match marker_type(ty, &self.params) {
+            Some(marker) => {
+                *ty = marker;
+                self.restored = true;
+            }
+            None => visit_mut::visit_type_mut(self, ty),
+        }
     }
 }
 
-fn field_named(fields: &Fields) -> Vec<FieldInfo> {
-    let mut res = Vec::new();
+fn marker_type(ty: &Type, params: &[Ident]) -> Option<Type> {
+    let path = match ty {
+        Type::Path(TypePath { qself, path }) => (qself, path),
+        _ => return None,
+    };
 
-    for field in fields {
-        let ident = field.ident.as_ref().cloned();
-        let ident = ident.expect(concat!(
-            "Named field doesn't have name.  ",
-            "This is a bug in `proconio`.  ",
-            "Please report this issue from ",
-            "<https://github.com/statiolake/proconio-rs/issues>."
-        ));
-        let ty = field.ty.clone();
-        let read = quote! {
-            let #ident = <#ty as ::proconio::source::Readable>::read(source);
-        };
+    match path {
+        // `<Usize1 as ::proconio::source::Readable>::Output`
+        (Some(qself), path) => {
+            let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+            let is_replaced = path.leading_colon.is_some()
+                && (segments == ["proconio", "source", "Readable", "Output"]
+                    || segments == ["proconio", "output", "Writable", "Value"]);
+
+            if is_replaced {
+                Some((*qself.ty).clone())
+            } else {
+                None
+            }
+        }
 
-        res.push(FieldInfo { ident, read });
+        // `T::Output` where `T` is a type parameter
+        (None, path) => match path.segments.iter().collect::<Vec<_>>()[..] {
+            [param, assoc]
+                if path.leading_colon.is_none()
+                    && params.contains(&param.ident)
+                    && param.arguments.is_empty()
+                    && (assoc.ident == "Output" || assoc.ident == "Value") =>
+            {
+                let param = &param.ident;
+                Some(parse_quote!(#param))
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Gets the type replacing the marker type: `<Marker as Trait>::Assoc`.
+///
+/// Type parameters are replaced with `T::Assoc` instead, since `#[derive(PartialEq)]` and others
+/// add the bounds only for the associated types in this form.
+pub(crate) fn replaced_type(
+    marker: &Type,
+    params: &[Ident],
+    trait_path: TokenStream2,
+    assoc: &str,
+) -> Type {
+    let assoc = Ident::new(assoc, Span2::call_site());
+    if let Type::Path(TypePath { qself: None, path }) = marker {
+        match path.get_ident() {
+            Some(param) if params.contains(param) => return parse_quote!(#param::#assoc),
+            _ => {}
+        }
     }
 
-    res
+    parse_quote!(<#marker as #trait_path>::#assoc)
+}
+
+pub(crate) fn type_params(generics: &Generics) -> Vec<Ident> {
+    generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect()
 }
 
-fn field_unnamed(fields: &Fields) -> Vec<FieldInfo> {
+fn fields_mut(ast: &mut DeriveInput) -> Vec<&mut Field> {
+    match &mut ast.data {
+        Data::Struct(data) => data.fields.iter_mut().collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter_mut()
+            .flat_map(|variant| variant.fields.iter_mut())
+            .collect(),
+        Data::Union(_) => Vec::new(),
+    }
+}
+
+struct FieldInfo {
+    ident: Ident,
+    read: TokenStream2,
+}
+
+fn field_info(fields: &Fields) -> Result<Vec<FieldInfo>, TokenStream> {
     let mut res = Vec::new();
 
     for (idx, field) in fields.iter().enumerate() {
-        let ident = format!("field{idx}");
-        let ident = Ident::new(&ident, Span2::call_site());
-        let ty = field.ty.clone();
+        // unnamed fields are read into `field0`, `field1`, ...
+        let ident = match &field.ident {
+            Some(ident) => ident.clone(),
+            None => Ident::new(&format!("field{idx}"), Span2::call_site()),
+        };
+        let value = read_field(field)?;
         let read = quote! {
-            let #ident = <#ty as ::proconio::source::Readable>::read(source);
+            let #ident = #value;
         };
 
         res.push(FieldInfo { ident, read });
     }
 
-    res
+    Ok(res)
+}
+
+fn read_field(field: &Field) -> Result<TokenStream2, TokenStream> {
+    let read = match field_attr::parse(field)? {
+        FieldKind::Readable => {
+            let ty = &field.ty;
+            quote!(<#ty as ::proconio::source::Readable>::read(source))
+        }
+        FieldKind::As(reader) => quote!(<#reader as ::proconio::source::Readable>::read(source)),
+        FieldKind::Len { elem, len, reader } => {
+            let elem = reader.unwrap_or(elem);
+            quote!(::proconio::read_value!(from &mut *source, [#elem; #len]))
+        }
+        FieldKind::Kind(kind) => quote!(::proconio::read_value!(from &mut *source, #kind)),
+        FieldKind::With(with) => quote!(::proconio::read_value!(from &mut *source, with #with)),
+        FieldKind::Default(Some(default)) => quote!(#default),
+        FieldKind::Default(None) => quote!(::std::default::Default::default()),
+    };
+
+    Ok(read)
 }
 
-fn generate(fields: &Fields, name: &Ident, field_info: &[FieldInfo]) -> TokenStream2 {
+fn generate(fields: &Fields, name: &TokenStream2, field_info: &[FieldInfo]) -> TokenStream2 {
     let idents = field_info.iter().map(|f| &f.ident);
 
     match fields {
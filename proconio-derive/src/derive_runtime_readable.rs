@@ -0,0 +1,146 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use crate::derive_readable::{error_at, read_value, replace_type, restore_markers};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, parse_macro_input, DeriveInput, Ident, Token, Type};
+
+/// A parameter like `n: usize`.
+struct Param {
+    name: Ident,
+    ty: Type,
+}
+
+impl Parse for Param {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+
+        Ok(Param { name, ty })
+    }
+}
+
+/// The arguments of the attribute: `params(n: usize, m: usize)` and `name = Spec`.
+#[derive(Default)]
+struct Options {
+    params: Vec<Param>,
+    name: Option<Ident>,
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = Options::default();
+
+        while !input.is_empty() {
+            let option = Ident::parse_any(input)?;
+            match &*option.to_string() {
+                "params" => {
+                    let content;
+                    parenthesized!(content in input);
+                    let params = Punctuated::<Param, Token![,]>::parse_terminated(&content)?;
+                    options.params.extend(params);
+                }
+                "name" => {
+                    input.parse::<Token![=]>()?;
+                    options.name = Some(input.parse()?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        option.span(),
+                        format!("unknown option `{option}`; expected `params` or `name`."),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub fn main(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let options = match Options::parse.parse(attr) {
+        Ok(options) => options,
+        Err(e) => {
+            let message = e.to_string();
+            return crate::compile_error_at(quote!(#message), e.span(), e.span())
+                .into_token_stream()
+                .into();
+        }
+    };
+
+    let mut ast = parse_macro_input!(input as DeriveInput);
+    restore_markers(&mut ast);
+
+    if !ast.generics.params.is_empty() {
+        return error_at(
+            "RuntimeReadable cannot be derived for generic types.",
+            &ast.generics,
+        );
+    }
+
+    // derive actually RuntimeReadable
+    let derive = match derive_runtime_readable_impl(&ast, &options) {
+        Ok(derive) => derive,
+        Err(error) => return error,
+    };
+
+    // modify AST to use actual Readable::Output type
+    if let Err(error) = replace_type(&mut ast) {
+        return error;
+    }
+
+    quote!(#ast #derive).into()
+}
+
+fn derive_runtime_readable_impl(
+    ast: &DeriveInput,
+    options: &Options,
+) -> Result<TokenStream2, TokenStream> {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let spec = options
+        .name
+        .clone()
+        .unwrap_or_else(|| format_ident!("{}Spec", name));
+    let read = read_value(ast, "RuntimeReadable")?;
+
+    let param_names: Vec<_> = options.params.iter().map(|param| &param.name).collect();
+    let param_types = options.params.iter().map(|param| &param.ty);
+    let doc = format!("The parameters to read [`{name}`] by `with` in `input!`.");
+
+    // The parameters are bound to the local variables, so that the fields can refer to them.
+    let res = quote! {
+        #[doc = #doc]
+        #vis struct #spec {
+            #(pub #param_names: #param_types,)*
+        }
+
+        impl ::proconio::source::RuntimeReadable for #spec {
+            type Output = #name;
+            #[allow(unused_variables)]
+            fn read<__R: ::std::io::BufRead, __S: ::proconio::source::Source<__R>>(
+                self,
+                source: &mut __S,
+            ) -> #name {
+                let #spec { #(#param_names,)* } = self;
+                #read
+            }
+        }
+    };
+
+    Ok(res)
+}
@@ -0,0 +1,221 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use crate::derive_readable::{
+    add_bounds, check_no_attr, error_at, get_data, get_data_mut, get_name, has_attr, impl_generics,
+    replaced_type, restore_markers, type_params,
+};
+use crate::field_attr::{self, FieldKind};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use quote::ToTokens;
+use syn::parse_macro_input;
+use syn::parse_quote;
+use syn::{DeriveInput, Field, Ident, Index, Member, Type, WherePredicate};
+
+pub fn main(attr: TokenStream, input: TokenStream) -> TokenStream {
+    if let Err(error) = check_no_attr(attr) {
+        return error;
+    }
+
+    let mut ast = parse_macro_input!(input as DeriveInput);
+    let paired = restore_markers(&mut ast) || has_attr(&ast, "derive_readable");
+
+    // derive actually Writable
+    let derive = match derive_writable_impl(&ast, paired) {
+        Ok(derive) => derive,
+        Err(error) => return error,
+    };
+
+    // modify AST to use actual Writable::Value type
+    if let Err(error) = replace_type(&mut ast) {
+        return error;
+    }
+
+    // the replaced types require the type parameters to be Writable
+    add_bounds(&mut ast.generics, writable_bounds);
+
+    quote!(#ast #derive).into()
+}
+
+fn replace_type(ast: &mut DeriveInput) -> Result<(), TokenStream> {
+    let params = type_params(&ast.generics);
+    // `#[readable]` is left for `#[derive_readable]` if it is not expanded yet.
+    let keep_attrs = has_attr(ast, "derive_readable");
+    let data = get_data_mut(ast, "Writable")?;
+
+    for field in data.fields.iter_mut() {
+        let writer = writer(field)?;
+        if !keep_attrs {
+            field
+                .attrs
+                .retain(|attr| !field_attr::is_readable_attr(attr));
+        }
+        if !matches!(writer, Writer::Type) {
+            // the type is written as the actual type
+            continue;
+        }
+
+        let (start, end) = {
+            let ty = field.ty.clone().into_token_stream();
+            crate::get_span_range(ty.into())
+        };
+
+        let new_ty = replaced_type(
+            &field.ty,
+            &params,
+            quote!(::proconio::output::Writable),
+            "Value",
+        );
+
+        // Restore original spanning info
+        let respanned = crate::set_span_range(new_ty, start, end);
+        field.ty = respanned;
+    }
+
+    Ok(())
+}
+
+fn derive_writable_impl(ast: &DeriveInput, paired: bool) -> Result<TokenStream2, TokenStream> {
+    let name = get_name(ast);
+    let fields = &get_data(ast, "Writable")?.fields;
+
+    let mut written = Vec::new();
+    for (idx, field) in fields.iter().enumerate() {
+        let ty = match writer(field)? {
+            Writer::Skip => continue,
+            Writer::Type => field.ty.clone(),
+            Writer::As(ty) => *ty,
+        };
+        written.push((idx, field, ty));
+    }
+    let fields = written;
+
+    let writes = fields.iter().map(|(idx, field, ty)| {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(*idx)),
+        };
+
+        quote! {
+            ::proconio::output::__write_field::<#ty, _>(&value.#member, &mut prev, buf);
+        }
+    });
+
+    // A struct with a single field is written just like the field, and the one with multiple
+    // fields is placed in its own line like a tuple.  The one without fields writes nothing.
+    let sep = match fields.len() {
+        0 => quote!(""),
+        1 => {
+            let ty = fields.iter().map(|(_, _, ty)| ty);
+            quote!(#(<#ty as ::proconio::output::Writable>::SEP)*)
+        }
+        _ => quote!("\n"),
+    };
+
+    let generics = impl_generics(ast, writable_bounds, paired);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let res = quote! {
+        impl #impl_generics ::proconio::output::Writable for #name #ty_generics #where_clause {
+            type Value = Self;
+            const SEP: &'static str = #sep;
+            #[allow(unused_mut, unused_variables)]
+            fn write(value: &Self, buf: &mut ::std::vec::Vec<u8>) {
+                let mut prev: ::std::option::Option<&'static str> = ::std::option::Option::None;
+                #(#writes)*
+            }
+        }
+    };
+
+    Ok(res)
+}
+
+pub(crate) fn writable_bounds(param: &Ident) -> Vec<WherePredicate> {
+    vec![
+        parse_quote!(#param: ::proconio::output::Writable),
+        // fields cannot have unsized types like `str`
+        parse_quote!(<#param as ::proconio::output::Writable>::Value: ::std::marker::Sized),
+    ]
+}
+
+/// How to write a field, following its `#[readable(...)]` attribute.
+enum Writer {
+    /// Not written since it is not in the input, marked by `skip` or `default`.
+    Skip,
+    /// Written by the field type.
+    Type,
+    /// Written by the type given by `as`, or the one built from `len` or `kind`.
+    As(Box<Type>),
+}
+
+fn writer(field: &Field) -> Result<Writer, TokenStream> {
+    let writer = match field_attr::parse(field)? {
+        FieldKind::Readable | FieldKind::Len { reader: None, .. } => Writer::Type,
+        FieldKind::Default(_) => Writer::Skip,
+        FieldKind::As(reader) => Writer::As(reader),
+        FieldKind::Len {
+            reader: Some(reader),
+            ..
+        } => Writer::As(Box::new(parse_quote!(::std::vec::Vec<#reader>))),
+        FieldKind::Kind(kind) => {
+            let writer = syn::parse2::<Type>(kind.clone())
+                .ok()
+                .and_then(kind_writer)
+                .ok_or_else(|| {
+                    error_at(
+                        concat!(
+                            "cannot write the field read by this kind; ",
+                            "only the types, arrays and tuples are supported in `kind` ",
+                            "with `#[derive_writable]`."
+                        ),
+                        &kind,
+                    )
+                })?;
+            Writer::As(Box::new(writer))
+        }
+        FieldKind::With(with) => {
+            return Err(error_at(
+                concat!(
+                    "cannot write the field read by `with`; ",
+                    "implement `Writable` manually instead of `#[derive_writable]`."
+                ),
+                &with,
+            ))
+        }
+    };
+
+    Ok(writer)
+}
+
+/// Builds the type writing the value read by the kind, where `[T; n]` is written as `Vec<T>` and
+/// `[T]` as `LenPrefixed<T>` to write its length back.  Returns `None` if the kind is not a type,
+/// like the one followed by `as` or `unzip`.
+fn kind_writer(kind: Type) -> Option<Type> {
+    let writer = match kind {
+        Type::Array(array) => {
+            let elem = kind_writer(*array.elem)?;
+            parse_quote!(::std::vec::Vec<#elem>)
+        }
+        Type::Slice(slice) => {
+            let elem = kind_writer(*slice.elem)?;
+            parse_quote!(::proconio::marker::LenPrefixed<#elem>)
+        }
+        Type::Tuple(mut tuple) => {
+            for elem in tuple.elems.iter_mut() {
+                *elem = kind_writer(elem.clone())?;
+            }
+            Type::Tuple(tuple)
+        }
+        Type::Paren(paren) => kind_writer(*paren.elem)?,
+        Type::Path(path) => Type::Path(path),
+        _ => return None,
+    };
+
+    Some(writer)
+}
@@ -0,0 +1,183 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use crate::args::{self, Arg};
+use crate::fastout::OutOptions;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote};
+use syn::{Block, ItemFn, ReturnType, Stmt};
+
+/// How many times the body of the function is run.
+enum Cases {
+    /// Runs the body once.  This is the same as `#[fastout]`.
+    Single,
+
+    /// Reads the number of test cases first, and runs the body that many times.
+    Multi,
+
+    /// Runs the body until stdin reaches EOF.
+    UntilEof,
+}
+
+#[derive(Default)]
+struct Options {
+    cases: Option<(Cases, Arg)>,
+    stack: Option<TokenStream2>,
+    out: OutOptions,
+}
+
+pub fn main(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let mut itemfn: ItemFn = parse_macro_input!(input as ItemFn);
+
+    let options = match parse_args(attr.into()) {
+        Ok(options) => options,
+        Err(compile_errors) => {
+            itemfn.block.stmts = compile_errors;
+            return itemfn.into_token_stream().into();
+        }
+    };
+    let cases = options.cases.map_or(Cases::Single, |(cases, _)| cases);
+
+    if let Cases::Multi | Cases::UntilEof = cases {
+        if let Err(mut compile_errors) = check_signature(&itemfn) {
+            // keep the original body so that the signature error is the only error reported.
+            compile_errors.append(&mut itemfn.block.stmts);
+            itemfn.block.stmts = compile_errors;
+            return itemfn.into_token_stream().into();
+        }
+    }
+
+    let compile_errors = crate::intercept::rewrite(&mut itemfn.block, options.out.debug.is_some());
+    if !compile_errors.is_empty() {
+        itemfn.block.stmts = compile_errors;
+        return itemfn.into_token_stream().into();
+    }
+
+    crate::fast_print::rewrite(&mut itemfn.block);
+
+    let block = match cases {
+        Cases::Single => crate::fastout::insert_new_print_macros(&itemfn.block, &options.out),
+        Cases::Multi => run_cases(
+            &itemfn.block,
+            quote!(0..::proconio::read_value!(usize)),
+            &options.out,
+        ),
+        Cases::UntilEof => run_cases(
+            &itemfn.block,
+            quote!((0..).take_while(|_| !::proconio::is_stdin_empty())),
+            &options.out,
+        ),
+    };
+
+    itemfn.block = Box::new(match options.stack {
+        Some(stack) => crate::fastout::spawn_with_stack(&block, &itemfn.sig.output, stack),
+        None => block,
+    });
+
+    itemfn.into_token_stream().into()
+}
+
+fn parse_args(attr: TokenStream2) -> Result<Options, Vec<Stmt>> {
+    let mut options = Options::default();
+
+    for arg in args::parse(attr)? {
+        if options.out.parse_arg(&arg)? {
+            continue;
+        }
+
+        let cases = match arg.name.to_string().as_str() {
+            "multi" => Cases::Multi,
+            "until_eof" => Cases::UntilEof,
+            "stack" => {
+                let stack = args::stack_size(&arg)?;
+                args::set_once(&mut options.stack, &arg, stack)?;
+                continue;
+            }
+            name => {
+                return Err(vec![args::error_at(
+                    &arg,
+                    &format!(
+                        "unknown option `{name}`; \
+                         expected `multi`, `until_eof`, `stack`, `capacity`, `sink`, `flush`, \
+                         `flush_on_panic` or `debug`."
+                    ),
+                )])
+            }
+        };
+
+        args::no_value(&arg)?;
+
+        if let Some((_, prev)) = &options.cases {
+            return Err(vec![args::error_at(
+                &arg,
+                &format!(
+                    "`{}` cannot be used together with `{}`.",
+                    arg.name, prev.name
+                ),
+            )]);
+        }
+
+        options.cases = Some((cases, arg));
+    }
+
+    Ok(options)
+}
+
+fn check_signature(itemfn: &ItemFn) -> Result<(), Vec<Stmt>> {
+    let sig = &itemfn.sig;
+
+    if !sig.inputs.is_empty() {
+        return Err(vec![crate::compile_error_at(
+            quote!("a function running multiple test cases cannot take arguments."),
+            sig.inputs.span(),
+            sig.inputs.span(),
+        )]);
+    }
+
+    if let ReturnType::Type(_, ty) = &sig.output {
+        return Err(vec![crate::compile_error_at(
+            quote!("a function running multiple test cases cannot return a value."),
+            ty.span(),
+            ty.span(),
+        )]);
+    }
+
+    Ok(())
+}
+
+fn run_cases(block: &Block, cases: TokenStream2, options: &OutOptions) -> Block {
+    let prelude = crate::fastout::stdout_prelude(options);
+    let flush = crate::fastout::flush_stdout();
+
+    parse_quote! {{
+        #prelude
+
+        // Reports which test case was running when the body panics.
+        struct __ProconioCaseGuard(usize);
+        impl ::std::ops::Drop for __ProconioCaseGuard {
+            fn drop(&mut self) {
+                if ::std::thread::panicking() {
+                    ::std::eprintln!("note: panicked while solving test case #{} (1-indexed)", self.0 + 1);
+                }
+            }
+        }
+
+        for __proconio_case in #cases {
+            let __proconio_guard = __ProconioCaseGuard(__proconio_case);
+            // Runs the body in a closure so that `return` finishes only the current test case.
+            let __proconio_body = || #block;
+            __proconio_body();
+            ::std::mem::drop(__proconio_guard);
+        }
+
+        #flush
+    }}
+}
@@ -0,0 +1,117 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Rewrites `print!` and `println!` having a simple format string into the fast path.
+//!
+//! When the format string is `"{}"` or `{}`s joined by spaces like `"{} {}"`, the arguments are
+//! written directly into the buffered stdout by `proconio::out::FastWrite`, or by `Display` if the
+//! type doesn't implement it.  Other invocations are left as is, and handled by the shadowed macros.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote_spanned};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Block, Expr, ExprLit, Lit, Macro, Token};
+
+pub fn rewrite(block: &mut Block) {
+    FastPrint.visit_block_mut(block);
+}
+
+struct FastPrint;
+
+impl VisitMut for FastPrint {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        visit_mut::visit_expr_mut(self, expr);
+
+        if let Expr::Macro(expr_macro) = expr {
+            if !expr_macro.attrs.is_empty() {
+                return;
+            }
+
+            if let Some(tokens) = fast_path(&expr_macro.mac) {
+                *expr = syn::parse2(tokens).expect(concat!(
+                    "Failed to parse the fast path of print!.  ",
+                    "This is a bug in `proconio`.  ",
+                    "Please report this issue from ",
+                    "<https://github.com/statiolake/proconio-rs/issues>."
+                ));
+            }
+        }
+    }
+}
+
+/// Generates the fast path of the macro invocation, or returns `None` if it is not applicable.
+fn fast_path(mac: &Macro) -> Option<TokenStream2> {
+    let newline = match mac.path.get_ident()?.to_string().as_str() {
+        "print" => false,
+        "println" => true,
+        _ => return None,
+    };
+
+    let args = Punctuated::<Expr, Token![,]>::parse_terminated
+        .parse2(mac.tokens.clone())
+        .ok()?;
+    let mut args = args.into_iter();
+    let format = match args.next()? {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(format),
+            ..
+        }) => format.value(),
+        _ => return None,
+    };
+    let args: Vec<Expr> = args.collect();
+
+    // `name = value` is a named argument, not an assignment.
+    if args.iter().any(|arg| matches!(arg, Expr::Assign(_))) {
+        return None;
+    }
+    if args.is_empty() || format.split(' ').any(|part| part != "{}") {
+        return None;
+    }
+    if format.split(' ').count() != args.len() {
+        // Let the standard library report the mismatch.
+        return None;
+    }
+
+    let span = mac.span();
+    let names: Vec<_> = (0..args.len())
+        .map(|i| format_ident!("__proconio_arg{}", i, span = span))
+        .collect();
+    let writes = names.iter().enumerate().map(|(i, name)| {
+        let sep = if i == 0 {
+            quote_spanned!(span=>)
+        } else {
+            quote_spanned!(span=> __proconio_buf.push(b' ');)
+        };
+
+        quote_spanned! {span=>
+            #sep
+            (&&::proconio::out::__Wrap(#name)).__proconio_write(__proconio_buf);
+        }
+    });
+    let newline = if newline {
+        quote_spanned!(span=> __proconio_buf.push(b'\n');)
+    } else {
+        quote_spanned!(span=>)
+    };
+
+    // The arguments are evaluated before locking the buffer, since they may print something.
+    Some(quote_spanned! {span=>
+        {
+            #(let #names = &(#args);)*
+            ::proconio::out::__write_with(|__proconio_buf| {
+                #[allow(unused_imports)]
+                use ::proconio::out::{__ViaDisplay as _, __ViaFastWrite as _};
+                #(#writes)*
+                #newline
+            })
+            .unwrap();
+        }
+    })
+}
@@ -5,140 +5,253 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
 // distributed except according to those terms.
 
+use crate::args::{self, Arg};
 use proc_macro::TokenStream;
-use proc_macro2::Span as Span2;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use quote::ToTokens;
-use syn::spanned::Spanned;
-use syn::visit::{self, Visit};
 use syn::{parse_macro_input, parse_quote};
-use syn::{Block, ExprClosure, ExprMacro, ItemFn, Macro, Path, Stmt};
+use syn::{Block, ItemFn, ReturnType, Stmt};
 
 pub fn main(attr: TokenStream, input: TokenStream) -> TokenStream {
     let mut itemfn: ItemFn = parse_macro_input!(input as ItemFn);
 
-    if !attr.is_empty() {
-        let (start, end) = crate::get_span_range(attr);
-
-        let compile_error = crate::compile_error_at(
-            quote!("no extra attribute is suppported."),
-            Span2::from(start),
-            Span2::from(end),
-        );
-
-        itemfn.block.stmts = vec![compile_error];
-        return itemfn.into_token_stream().into();
-    }
+    let options = match parse_args(attr.into()) {
+        Ok(options) => options,
+        Err(compile_errors) => {
+            itemfn.block.stmts = compile_errors;
+            return itemfn.into_token_stream().into();
+        }
+    };
 
-    if let Err(compile_errors) = error_for_print_macros_in_closures(&itemfn.block) {
+    let compile_errors = crate::intercept::rewrite(&mut itemfn.block, options.out.debug.is_some());
+    if !compile_errors.is_empty() {
         itemfn.block.stmts = compile_errors;
         return itemfn.into_token_stream().into();
     }
 
-    itemfn.block = Box::new(insert_new_print_macros(&itemfn.block));
+    crate::fast_print::rewrite(&mut itemfn.block);
+
+    let block = insert_new_print_macros(&itemfn.block, &options.out);
+    itemfn.block = Box::new(match options.stack {
+        Some(stack) => spawn_with_stack(&block, &itemfn.sig.output, stack),
+        None => block,
+    });
 
     itemfn.into_token_stream().into()
 }
 
-fn error_for_print_macros_in_closures(block: &Block) -> std::result::Result<(), Vec<Stmt>> {
-    let mut visitor = BlockVisitor::default();
-    visitor.visit_block(block);
-    return if visitor.compile_errors.is_empty() {
-        Ok(())
-    } else {
-        Err(visitor.compile_errors)
-    };
+#[derive(Default)]
+struct Options {
+    stack: Option<TokenStream2>,
+    out: OutOptions,
+}
 
-    #[derive(Default)]
-    struct BlockVisitor {
-        compile_errors: Vec<Stmt>,
-    }
+fn parse_args(attr: TokenStream2) -> Result<Options, Vec<Stmt>> {
+    let mut options = Options::default();
 
-    impl<'ast> Visit<'ast> for BlockVisitor {
-        fn visit_expr_closure(&mut self, item: &'ast ExprClosure) {
-            let mut visitor = ClosureVisitor::default();
-            visitor.visit_expr_closure(item);
-            self.compile_errors.extend(visitor.compile_errors);
+    for arg in args::parse(attr)? {
+        if options.out.parse_arg(&arg)? {
+            continue;
         }
-    }
 
-    #[derive(Default)]
-    struct ClosureVisitor {
-        compile_errors: Vec<Stmt>,
-    }
-
-    impl<'ast> Visit<'ast> for ClosureVisitor {
-        fn visit_expr_macro(&mut self, item: &'ast ExprMacro) {
-            let Macro { path, .. } = &item.mac;
-            if equals(path, "print") || equals(path, "println") {
-                // Closure containing print macro is prohibited because closure *may* passed to the
-                // function which requires the closure to be `Send`.  For example, std::thread::spawn()
-                // takes an closure and run the closure in another thread.  That causes an error since
-                // StdoutLock is not thread-safe.  The problem is, the error message is too-complecated
-                // for beginners since the error originates from invisible codes inserted by this
-                // procedural macro.  Yes, if the closure don't have to be `Send`, it's OK to have
-                // print macros in it.  However such a trait boundary is not yet resolved at the time
-                // of macro expansion, so it is impossible to change behavior selectively here.  For
-                // that reason, all closures must not have print! call in it regardless of whether it
-                // requires `Send` or not for now.
-
-                // emit an error for each position of print macro.
-                self.compile_errors.push(crate::compile_error_at(
-                    quote!(
-                        "Closures in a #[fastout] function cannot contain `print!` or \
-                        `println!` macro\n\
-                        \n\
-                        note: If you want to run your entire logic in a thread having extended \
-                        size of stack, you can define a new function instead.  See \
-                        documentation (https://docs.rs/proconio/#\
-                        closures-having-print-or-println-in-fastout-function) for more \
-                        details.\n\
-                        \n\
-                        note: This is because if you use this closure with \
-                        `std::thread::spawn()` or any other functions requiring `Send` for an \
-                        argument closure, the compiler emits an error about thread unsafety for \
-                        our internal implementations.  If you are using the closure just in a \
-                        single thread, it's actually no problem, but we cannot check the trait \
-                        bounds at the macro-expansion time.  So for now, all closures having \
-                        `print!` or `println!` is prohibited regardless of the `Send` \
-                        requirements."
+        match arg.name.to_string().as_str() {
+            "stack" => {
+                let stack = args::stack_size(&arg)?;
+                args::set_once(&mut options.stack, &arg, stack)?;
+            }
+            name => {
+                return Err(vec![args::error_at(
+                    &arg,
+                    &format!(
+                        "unknown option `{name}`; \
+                         expected `stack`, `capacity`, `sink`, `flush`, `flush_on_panic` or `debug`."
                     ),
-                    item.span(),
-                    item.span(),
-                ));
+                )])
             }
-            visit::visit_expr_macro(self, item);
         }
     }
 
-    fn equals(path: &Path, ident: &str) -> bool {
-        matches!(path.get_ident(), Some(path_ident) if path_ident == ident)
+    Ok(options)
+}
+
+/// Options configuring the buffered stdout, shared by `#[fastout]` and `#[proconio::main]`.
+#[derive(Default)]
+pub(crate) struct OutOptions {
+    capacity: Option<TokenStream2>,
+    sink: Option<TokenStream2>,
+    flush: Option<TokenStream2>,
+    flush_on_panic: Option<()>,
+    pub(crate) debug: Option<()>,
+}
+
+impl OutOptions {
+    /// Parses the argument if it is one of the options for the buffered stdout.  Returns whether
+    /// the argument is consumed.
+    pub(crate) fn parse_arg(&mut self, arg: &Arg) -> Result<bool, Vec<Stmt>> {
+        match arg.name.to_string().as_str() {
+            "capacity" => {
+                let capacity = args::capacity(arg)?;
+                args::set_once(&mut self.capacity, arg, capacity)?;
+            }
+            "sink" => {
+                let sink = args::sink(arg)?;
+                args::set_once(&mut self.sink, arg, sink)?;
+            }
+            "flush" => {
+                let flush = args::flush_policy(arg)?;
+                args::set_once(&mut self.flush, arg, flush)?;
+            }
+            "flush_on_panic" => {
+                args::no_value(arg)?;
+                args::set_once(&mut self.flush_on_panic, arg, ())?;
+            }
+            "debug" => {
+                args::no_value(arg)?;
+                args::set_once(&mut self.debug, arg, ())?;
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
     }
 }
 
-fn insert_new_print_macros(block: &Block) -> Block {
+pub(crate) fn insert_new_print_macros(block: &Block, options: &OutOptions) -> Block {
+    let prelude = stdout_prelude(options);
+    let flush = flush_stdout();
+
     parse_quote! {{
-        let __proconio_stdout = ::std::io::stdout();
-        let mut __proconio_stdout = ::std::io::BufWriter::new(__proconio_stdout.lock());
+        #prelude
+        let __proconio_res = #block;
+        #flush
+        __proconio_res
+    }}
+}
+
+/// Statements shadowing `print!` and `println!` to write into the buffered stdout.
+///
+/// The buffered stdout is a global one in `proconio::out`, so the shadowed macros can be used in
+/// closures and in other threads.  The returned statements also create a guard flushing the buffer
+/// on every exit path of the function, including an early `return`, `?` and a panic.  If any of
+/// `capacity`, `sink` and `flush` is given, the buffer is configured until the function exits.  In
+/// the debug mode, `eprint!`, `eprintln!` and `dbg!` are also shadowed to flush the buffer first.
+pub(crate) fn stdout_prelude(options: &OutOptions) -> TokenStream2 {
+    let flush_on_panic = match options.flush_on_panic {
+        Some(()) => quote!(::proconio::out::flush_on_panic();),
+        None => quote!(),
+    };
+
+    let configure = match options {
+        OutOptions {
+            capacity: None,
+            sink: None,
+            flush: None,
+            ..
+        } => quote!(),
+        OutOptions {
+            capacity,
+            sink,
+            flush,
+            ..
+        } => {
+            let (capacity, sink, flush) = (option(capacity), option(sink), option(flush));
+            quote! {
+                // restores the configuration after the flush on exit below.
+                let __proconio_out_config = ::proconio::out::__configure(#capacity, #sink, #flush);
+            }
+        }
+    };
+
+    // flushes the buffer before writing to stderr, so that the order with stdout is kept.
+    let debug = match options.debug {
+        Some(()) => quote! {
+            #[allow(unused_macros)]
+            macro_rules! eprint {
+                ($($tt:tt)*) => {{
+                    ::proconio::out::stdout().flush().unwrap();
+                    ::std::eprint!($($tt)*);
+                }};
+            }
+
+            #[allow(unused_macros)]
+            macro_rules! eprintln {
+                ($($tt:tt)*) => {{
+                    ::proconio::out::stdout().flush().unwrap();
+                    ::std::eprintln!($($tt)*);
+                }};
+            }
+
+            #[allow(unused_macros)]
+            macro_rules! dbg {
+                ($($tt:tt)*) => {{
+                    ::proconio::out::stdout().flush().unwrap();
+                    ::std::dbg!($($tt)*)
+                }};
+            }
+        },
+        None => quote!(),
+    };
+
+    quote! {
+        #debug
 
         #[allow(unused_macros)]
         macro_rules! print {
             ($($tt:tt)*) => {{
-                use std::io::Write as _;
-                ::std::write!(__proconio_stdout, $($tt)*).unwrap();
+                ::std::write!(::proconio::out::stdout(), $($tt)*).unwrap();
             }};
         }
 
         #[allow(unused_macros)]
         macro_rules! println {
             ($($tt:tt)*) => {{
-                use std::io::Write as _;
-                ::std::writeln!(__proconio_stdout, $($tt)*).unwrap();
+                ::std::writeln!(::proconio::out::stdout(), $($tt)*).unwrap();
             }};
         }
 
-        let __proconio_res = #block;
-        <::std::io::BufWriter<::std::io::StdoutLock> as ::std::io::Write>::flush(&mut __proconio_stdout).unwrap();
-        return __proconio_res;
+        #flush_on_panic
+        #configure
+        let __proconio_flush_on_drop = ::proconio::out::__flush_on_drop();
+    }
+}
+
+/// A statement flushing the buffered stdout by dropping the guard created in the prelude.  A flush
+/// error is reported by a panic.
+pub(crate) fn flush_stdout() -> TokenStream2 {
+    quote! {
+        // the body may diverge, such as by `panic!()` or `loop {}`.
+        #[allow(unreachable_code)]
+        ::std::mem::drop(__proconio_flush_on_drop);
+    }
+}
+
+fn option(value: &Option<TokenStream2>) -> TokenStream2 {
+    match value {
+        Some(value) => quote!(::std::option::Option::Some(#value)),
+        None => quote!(::std::option::Option::None),
+    }
+}
+
+/// Runs the block in a new thread with the specified size of stack, and waits for it.
+///
+/// The block should already have the print macros inserted, so that the buffered stdout is flushed
+/// in the new thread before it finishes.  A panic in the thread is propagated to the caller.
+pub(crate) fn spawn_with_stack(block: &Block, output: &ReturnType, stack: TokenStream2) -> Block {
+    let output = match output {
+        ReturnType::Default => quote!(-> ()),
+        ReturnType::Type(arrow, ty) => quote!(#arrow #ty),
+    };
+
+    parse_quote! {{
+        let __proconio_thread = ::std::thread::Builder::new()
+            .stack_size(#stack)
+            .spawn(move || #output #block)
+            .expect("failed to spawn a thread with the specified stack size");
+
+        match __proconio_thread.join() {
+            ::std::result::Result::Ok(__proconio_res) => __proconio_res,
+            ::std::result::Result::Err(__proconio_panic) => ::std::panic::resume_unwind(__proconio_panic),
+        }
     }}
 }
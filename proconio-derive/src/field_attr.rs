@@ -0,0 +1,188 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Parses the field attributes of `#[derive_readable]` and `#[derive(Readable)]` like
+//! `#[readable(len = n)]`.
+
+use crate::derive_readable::error_at;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Expr, ExprLit, Field, GenericArgument, Ident, Lit, PathArguments, Token, Type,
+};
+
+/// How to read a field.
+pub enum FieldKind {
+    /// Reads by the `Readable` implementation of the field type.
+    Readable,
+
+    /// `as = T`: reads by the `Readable` implementation of `T`, whose output is the field type.
+    As(Box<Type>),
+
+    /// `len = n`: reads `Vec<T>` having `n` elements of `T`, or of the type given by `as`.
+    Len {
+        elem: Box<Type>,
+        len: Box<Expr>,
+        reader: Option<Box<Type>>,
+    },
+
+    /// `kind = "..."`: reads by the kind in the grammar of `read_value!`.
+    Kind(TokenStream2),
+
+    /// `with = expr`: reads by the `RuntimeReadable`.
+    With(Expr),
+
+    /// `skip`, `default` or `default = expr`: doesn't read, and uses `Default::default()` or the
+    /// expression.
+    Default(Option<Expr>),
+}
+
+/// An argument of `#[readable(...)]`.  The value of `as` is a type, and the others are
+/// expressions.
+struct FieldArg {
+    name: Ident,
+    value: Option<TokenStream2>,
+}
+
+impl Parse for FieldArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = Ident::parse_any(input)?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            if name == "as" {
+                Some(input.parse::<Type>()?.into_token_stream())
+            } else {
+                Some(input.parse::<Expr>()?.into_token_stream())
+            }
+        } else {
+            None
+        };
+
+        Ok(FieldArg { name, value })
+    }
+}
+
+pub fn is_readable_attr(attr: &Attribute) -> bool {
+    attr.path.is_ident("readable")
+}
+
+/// Gets how to read the field from its `#[readable(...)]` attributes.
+pub fn parse(field: &Field) -> Result<FieldKind, TokenStream> {
+    let mut args = Vec::new();
+    let mut reader = None;
+    for attr in field.attrs.iter().filter(|attr| is_readable_attr(attr)) {
+        let parsed = attr
+            .parse_args_with(Punctuated::<FieldArg, Token![,]>::parse_terminated)
+            .map_err(|e| error_at(&e.to_string(), attr))?;
+        for arg in parsed {
+            if arg.name != "as" {
+                args.push(arg);
+            } else if reader.is_some() {
+                return Err(error_at("duplicate `as` option.", &arg.name));
+            } else {
+                reader = Some(Box::new(value::<Type>(&arg)?));
+            }
+        }
+    }
+
+    let arg = match (&*args, reader) {
+        ([], None) => return Ok(FieldKind::Readable),
+        ([], Some(reader)) => return Ok(FieldKind::As(reader)),
+        ([arg], Some(reader)) => {
+            if arg.name != "len" {
+                return Err(error_at("`as` can only be combined with `len`.", &arg.name));
+            }
+
+            return len(field, arg, Some(reader));
+        }
+        ([arg], None) => arg,
+        ([_, second, ..], _) => {
+            return Err(error_at(
+                "only one of `len`, `kind`, `with`, `skip` or `default` can be specified.",
+                &second.name,
+            ))
+        }
+    };
+
+    match &*arg.name.to_string() {
+        "len" => len(field, arg, None),
+        "kind" => match value::<Expr>(arg)? {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => lit
+                .parse()
+                .map(FieldKind::Kind)
+                .map_err(|e| error_at(&e.to_string(), &lit)),
+            value => Err(error_at(
+                "invalid kind; expected a string like `kind = \"[(Usize1, i64); m]\"`.",
+                &value,
+            )),
+        },
+        "with" => Ok(FieldKind::With(value(arg)?)),
+        "skip" => match &arg.value {
+            None => Ok(FieldKind::Default(None)),
+            Some(value) => Err(error_at("`skip` doesn't take a value.", value)),
+        },
+        "default" => match &arg.value {
+            None => Ok(FieldKind::Default(None)),
+            Some(_) => Ok(FieldKind::Default(Some(value(arg)?))),
+        },
+        _ => Err(error_at(
+            &format!(
+                "unknown option `{}`; expected `len`, `kind`, `with`, `as`, `skip` or `default`.",
+                arg.name
+            ),
+            &arg.name,
+        )),
+    }
+}
+
+fn len(field: &Field, arg: &FieldArg, reader: Option<Box<Type>>) -> Result<FieldKind, TokenStream> {
+    let elem = vec_elem(&field.ty)
+        .ok_or_else(|| error_at("`len` requires the field type to be `Vec<T>`.", &field.ty))?;
+
+    Ok(FieldKind::Len {
+        elem: Box::new(elem),
+        len: Box::new(value(arg)?),
+        reader,
+    })
+}
+
+/// Gets the element type `T` of `Vec<T>`.
+fn vec_elem(ty: &Type) -> Option<Type> {
+    let segment = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Vec" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.iter().collect::<Vec<_>>()[..] {
+            [GenericArgument::Type(elem)] => Some(elem.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn value<T: Parse>(arg: &FieldArg) -> Result<T, TokenStream> {
+    let value = arg.value.as_ref().ok_or_else(|| {
+        error_at(
+            &format!("`{}` requires a value like `{} = ...`.", arg.name, arg.name),
+            &arg.name,
+        )
+    })?;
+
+    // the value is already parsed as `T` in `FieldArg::parse`
+    syn::parse2(value.clone()).map_err(|e| error_at(&e.to_string(), value))
+}
@@ -0,0 +1,244 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Rewrites the paths writing to stdout directly, so that they go through the buffered stdout.
+//!
+//! The shadowed `print!` and `println!` only catch the bare macro names.  This module also
+//! rewrites the following paths in the body of the function:
+//!
+//! - `std::print!` and `std::println!` (and `std::eprint!`, `std::eprintln!` and `std::dbg!` in
+//!   the debug mode) into the bare macro names, which are shadowed.
+//! - `std::io::stdout` and `::std::io::stdout` into `proconio::out::stdout`, including the ones in
+//!   the arguments of macros like `writeln!(std::io::stdout(), ...)`.
+//!
+//! `io::stdout` is left as is, since `io` may be a module other than `std::io`.  `stdout` imported
+//! by `use` cannot be rewritten reliably, so it is reported as an error.
+
+use proc_macro2::TokenTree;
+use proc_macro2::{Group, Ident, Punct, Spacing, Span as Span2, TokenStream as TokenStream2};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Block, ExprPath, ItemUse, Macro, Path, Stmt, UseTree};
+
+/// Rewrites the body.  Returns `compile_error!` statements for the paths which cannot be
+/// rewritten.
+pub fn rewrite(block: &mut Block, debug: bool) -> Vec<Stmt> {
+    let mut visitor = Intercept {
+        debug,
+        errors: Vec::new(),
+    };
+    visitor.visit_block_mut(block);
+
+    visitor.errors
+}
+
+struct Intercept {
+    debug: bool,
+    errors: Vec<Stmt>,
+}
+
+impl Intercept {
+    fn is_intercepted_macro(&self, name: &str) -> bool {
+        match name {
+            "print" | "println" => true,
+            "eprint" | "eprintln" | "dbg" => self.debug,
+            _ => false,
+        }
+    }
+}
+
+impl VisitMut for Intercept {
+    fn visit_expr_path_mut(&mut self, expr: &mut ExprPath) {
+        if expr.qself.is_none() && is_stdout_path(&expr.path) {
+            let span = expr.path.span();
+            expr.path = syn::parse_quote_spanned!(span=> ::proconio::out::stdout);
+        }
+
+        visit_mut::visit_expr_path_mut(self, expr);
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        let segments = segments(&mac.path);
+        if let [std, name] = &*segments {
+            if std == "std" && self.is_intercepted_macro(name) {
+                let name = mac.path.segments.last().unwrap().ident.clone();
+                mac.path = Path::from(name);
+            }
+        }
+
+        let tokens = std::mem::take(&mut mac.tokens);
+        mac.tokens = self.rewrite_tokens(tokens);
+    }
+
+    fn visit_item_use_mut(&mut self, item: &mut ItemUse) {
+        let mut path = Vec::new();
+        if let Some(tree) = find_stdout_import(&item.tree, &mut path) {
+            self.errors.push(crate::compile_error_at(
+                quote!(concat!(
+                    "`#[fastout]` cannot intercept `std::io::stdout` imported by `use`; ",
+                    "write `std::io::stdout()` or import `proconio::out::stdout` instead."
+                )),
+                tree.span(),
+                tree.span(),
+            ));
+        }
+    }
+}
+
+impl Intercept {
+    /// Rewrites the paths in the tokens of a macro invocation, which are not parsed by `syn`.
+    fn rewrite_tokens(&self, tokens: TokenStream2) -> TokenStream2 {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut res = Vec::with_capacity(tokens.len());
+
+        let mut i = 0;
+        while i < tokens.len() {
+            // Don't match in the middle of a path like `foo::std::io::stdout`.
+            let in_path = (i >= 2 && is_colon_pair(&tokens[i - 2], &tokens[i - 1]))
+                || (i >= 1
+                    && matches!(tokens[i - 1], TokenTree::Ident(_))
+                    && skip_leading_colons(&tokens, i) != i);
+
+            if !in_path {
+                if let Some(end) = match_path(&tokens, i, &["std", "io", "stdout"]) {
+                    let span = tokens[i].span();
+                    res.extend(stdout_path_tokens(span));
+                    i = end;
+                    continue;
+                }
+
+                if let Some(end) = self.match_std_macro(&tokens, i) {
+                    // keeps the macro name and drops the `std::` prefix.
+                    res.push(tokens[end - 1].clone());
+                    i = end;
+                    continue;
+                }
+            }
+
+            res.push(match &tokens[i] {
+                TokenTree::Group(group) => {
+                    let mut new =
+                        Group::new(group.delimiter(), self.rewrite_tokens(group.stream()));
+                    new.set_span(group.span());
+                    TokenTree::Group(new)
+                }
+                token => token.clone(),
+            });
+            i += 1;
+        }
+
+        res.into_iter().collect()
+    }
+
+    /// Matches `std::name` followed by `!`, where `name` is an intercepted macro.  Returns the
+    /// index just after `name`.
+    fn match_std_macro(&self, tokens: &[TokenTree], i: usize) -> Option<usize> {
+        let start = skip_leading_colons(tokens, i);
+        match tokens.get(start..start + 5)? {
+            [TokenTree::Ident(std), c1, c2, TokenTree::Ident(name), TokenTree::Punct(bang)]
+                if std == "std"
+                    && is_colon_pair(c1, c2)
+                    && self.is_intercepted_macro(&name.to_string())
+                    && bang.as_char() == '!' =>
+            {
+                Some(start + 4)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn segments(path: &Path) -> Vec<String> {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect()
+}
+
+/// Whether the path is `std::io::stdout` or `::std::io::stdout`.
+fn is_stdout_path(path: &Path) -> bool {
+    segments(path) == ["std", "io", "stdout"]
+}
+
+/// Finds `stdout` imported from `std::io` in the use tree.
+fn find_stdout_import<'a>(tree: &'a UseTree, path: &mut Vec<String>) -> Option<&'a UseTree> {
+    let is_std_io = |path: &[String]| path == ["std", "io"];
+
+    match tree {
+        UseTree::Path(use_path) => {
+            path.push(use_path.ident.to_string());
+            let found = find_stdout_import(&use_path.tree, path);
+            path.pop();
+            found
+        }
+        UseTree::Name(name) if name.ident == "stdout" && is_std_io(path) => Some(tree),
+        UseTree::Rename(rename) if rename.ident == "stdout" && is_std_io(path) => Some(tree),
+        UseTree::Group(group) => group
+            .items
+            .iter()
+            .find_map(|tree| find_stdout_import(tree, path)),
+        _ => None,
+    }
+}
+
+fn is_colon_pair(first: &TokenTree, second: &TokenTree) -> bool {
+    match (first, second) {
+        (TokenTree::Punct(first), TokenTree::Punct(second)) => {
+            first.as_char() == ':' && first.spacing() == Spacing::Joint && second.as_char() == ':'
+        }
+        _ => false,
+    }
+}
+
+fn skip_leading_colons(tokens: &[TokenTree], i: usize) -> usize {
+    match tokens.get(i..i + 2) {
+        Some([c1, c2]) if is_colon_pair(c1, c2) => i + 2,
+        _ => i,
+    }
+}
+
+/// Matches the path consisting of the segments, with an optional leading `::`.  Returns the index
+/// just after the path.
+fn match_path(tokens: &[TokenTree], i: usize, segments: &[&str]) -> Option<usize> {
+    let mut pos = skip_leading_colons(tokens, i);
+    for (idx, segment) in segments.iter().enumerate() {
+        if idx != 0 {
+            match tokens.get(pos..pos + 2) {
+                Some([c1, c2]) if is_colon_pair(c1, c2) => pos += 2,
+                _ => return None,
+            }
+        }
+
+        match tokens.get(pos) {
+            Some(TokenTree::Ident(ident)) if ident == segment => pos += 1,
+            _ => return None,
+        }
+    }
+
+    // `io::stdout::foo` is not the function itself.
+    match tokens.get(pos..pos + 2) {
+        Some([c1, c2]) if is_colon_pair(c1, c2) => None,
+        _ => Some(pos),
+    }
+}
+
+/// Tokens of `::proconio::out::stdout` having the span.
+fn stdout_path_tokens(span: Span2) -> Vec<TokenTree> {
+    let mut tokens = Vec::new();
+    for segment in &["proconio", "out", "stdout"] {
+        let mut first = Punct::new(':', Spacing::Joint);
+        first.set_span(span);
+        let mut second = Punct::new(':', Spacing::Alone);
+        second.set_span(span);
+        tokens.push(TokenTree::Punct(first));
+        tokens.push(TokenTree::Punct(second));
+        tokens.push(TokenTree::Ident(Ident::new(segment, span)));
+    }
+
+    tokens
+}
@@ -8,9 +8,11 @@
 #![recursion_limit = "128"]
 #![allow(clippy::needless_doctest_main)]
 
-//! Macros to easily derive `Readable` and make stdout faster.
+//! Macros to easily derive `Readable` and `Writable`, and make stdout faster.
 //!
-//! proconio_derive provides two procedural macros (attributes): `derive_readable` and `fastout`.
+//! proconio_derive provides five procedural macros (attributes): `derive_readable`,
+//! `derive_writable`, `derive_runtime_readable`, `fastout` and `main`, and a derive macro
+//! `Readable`.
 //!
 //! # Examples for `#[derive_readable]`
 //!
@@ -67,6 +69,25 @@
 //!     println!("{}", 123456789);     // "123456789\n"
 //! }
 //! ```
+//!
+//! # Examples for `#[main]`
+//!
+//! ```
+//! use proconio::input;
+//! use proconio_derive::main;
+//!
+//! // Reads the number of test cases `t` first, and then runs the body `t` times.
+//! #[main(multi)]
+//! fn solve() {
+//!     input! {
+//!         n: usize,
+//!         a: [i64; n],
+//!     }
+//!     println!("{}", a.iter().sum::<i64>());
+//! }
+//! #
+//! # fn main() {}
+//! ```
 extern crate proc_macro;
 
 use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
@@ -75,8 +96,15 @@ use quote::ToTokens;
 use syn::parse::Parse;
 use syn::Stmt;
 
+mod args;
 mod derive_readable;
+mod derive_runtime_readable;
+mod derive_writable;
+mod entry;
+mod fast_print;
 mod fastout;
+mod field_attr;
+mod intercept;
 
 /// Derives `Readable` for your own type.
 ///
@@ -86,20 +114,295 @@ mod fastout;
 /// the output type of the read.  For example, if you have `Usize1` in your struct, it will
 /// actually be defined as `usize`.  Of course the `Usize1`'s  `Readable` implementation is used to
 /// read.
+///
+/// Fields can be customized by `#[readable(...)]` attributes.  Fields are read in the declaration
+/// order, so the expressions in the attributes can refer to the fields read before.
+///
+/// - `#[readable(len = n)]` reads `Vec<T>` having `n` elements, like `[T; n]` in `input!`.  The
+///   element type `T` is translated like other members, so `Vec<Usize1>` is actually `Vec<usize>`.
+/// - `#[readable(kind = "[(Usize1, i64); m]")]` reads by the kind given in the same grammar as
+///   `input!`.  The member type is not translated, so write the actual type like
+///   `Vec<(usize, i64)>`.
+/// - `#[readable(with = expr)]` reads by the `RuntimeReadable` given by `expr`, like `with` in
+///   `input!`.  The member type is not translated.
+/// - `#[readable(as = Usize1)]` reads by the given type instead of the member type.  The member
+///   type is not translated, so write the actual type like `usize`.  It can be combined with `len`
+///   to give the type of the elements.
+/// - `#[readable(skip)]` or `#[readable(default)]` doesn't read anything and uses
+///   `Default::default()`, and `#[readable(default = expr)]` uses the value of `expr`.
+///
+/// ```
+/// # extern crate proconio;
+/// # extern crate proconio_derive;
+/// use proconio::input;
+/// use proconio::marker::Usize1;
+/// # use proconio::source::auto::AutoSource;
+/// use proconio_derive::derive_readable;
+///
+/// #[derive_readable]
+/// struct Case {
+///     n: usize,
+///     m: usize,
+///     #[readable(len = n)]
+///     a: Vec<i64>,
+///     #[readable(kind = "[(Usize1, Usize1); m]")]
+///     edges: Vec<(usize, usize)>,
+///     #[readable(default = vec![false; n])]
+///     visited: Vec<bool>,
+/// }
+///
+/// # let source = AutoSource::from("3 2\n10 20 30\n1 2\n2 3");
+/// input! {
+/// #   from source,
+///     case: Case,
+/// }
+///
+/// assert_eq!(case.a, [10, 20, 30]);
+/// assert_eq!(case.edges, [(0, 1), (1, 2)]);
+/// assert_eq!(case.visited, [false; 3]);
+/// ```
+///
+/// Generic structs are supported as well.  Each type parameter `T` gets the bound `T: Readable`,
+/// both on the impl and on the struct itself, since a member of type `T` is actually defined as
+/// `T::Output`.  Lifetimes, const generics and where clauses are carried into the impl as is.
+///
+/// ```
+/// # extern crate proconio;
+/// # extern crate proconio_derive;
+/// use proconio::input;
+/// use proconio::marker::Usize1;
+/// # use proconio::source::auto::AutoSource;
+/// use proconio_derive::derive_readable;
+///
+/// #[derive_readable]
+/// #[derive(Debug)]
+/// struct Edge<W> {
+///     from: Usize1,
+///     to: Usize1,
+///     weight: W,
+/// }
+///
+/// # let source = AutoSource::from("1 2 3 2 3 0.5");
+/// input! {
+/// #   from source,
+///     edge: Edge<u32>,
+///     weighted: Edge<f64>,
+/// }
+///
+/// assert_eq!((edge.from, edge.to, edge.weight), (0, 1, 3));
+/// assert_eq!(weighted.weight, 0.5);
+/// ```
+///
+/// Enums can also derive `Readable`, for the inputs like queries where each line starts with a
+/// tag telling its kind.  The tag token is read first, and then the fields of the variant having
+/// the tag are read.  The tag of a variant is given by `#[tag = 1]` or `#[tag = "add"]`, and
+/// defaults to its 1-based position.  Reading an unknown tag panics with the list of the expected
+/// tags.
+///
+/// ```
+/// # extern crate proconio;
+/// # extern crate proconio_derive;
+/// use proconio::input;
+/// use proconio::marker::Usize1;
+/// # use proconio::source::auto::AutoSource;
+/// use proconio_derive::derive_readable;
+///
+/// #[derive_readable]
+/// #[derive(PartialEq, Debug)]
+/// enum Query {
+///     Add(Usize1, i64),         // tag 1
+///     Get { index: Usize1 },    // tag 2
+///     #[tag = "clear"]
+///     Clear,
+/// }
+///
+/// # let source = AutoSource::from("3\n1 2 10\n2 2\nclear");
+/// input! {
+/// #   from source,
+///     q: usize,
+///     queries: [Query; q],
+/// }
+///
+/// assert_eq!(
+///     queries,
+///     [Query::Add(1, 10), Query::Get { index: 1 }, Query::Clear],
+/// );
+/// ```
 #[proc_macro_attribute]
 pub fn derive_readable(attr: TokenStream, input: TokenStream) -> TokenStream {
     derive_readable::main(attr, input)
 }
 
+/// Derives `RuntimeReadable` for the parameters to read your own type.
+///
+/// Some types cannot be read without the values known at runtime, such as a graph with `n`
+/// vertices and `m` edges given by the input before it.  `#[derive_runtime_readable(params(n:
+/// usize, m: usize))]` generates a struct `<Name>Spec { n, m }` having the parameters as public
+/// fields, which implements `RuntimeReadable` with the output type `<Name>`.  Use it by `with` in
+/// `input!`, like `g: with GraphSpec { n, m }`.  The name of the generated struct can be changed by
+/// `name = ...`.
+///
+/// The members are read in the same way as `#[derive_readable]`, including the member types
+/// translated and the field attributes like `#[readable(len = m)]`.  The expressions in the field
+/// attributes can refer to the parameters as well as the members read before.  Generic types are
+/// not supported.
+///
+/// ```
+/// # extern crate proconio;
+/// # extern crate proconio_derive;
+/// use proconio::input;
+/// use proconio::marker::Usize1;
+/// # use proconio::source::auto::AutoSource;
+/// use proconio_derive::derive_runtime_readable;
+///
+/// #[derive_runtime_readable(params(n: usize, m: usize))]
+/// struct Graph {
+///     #[readable(kind = "[(Usize1, Usize1); m]")]
+///     edges: Vec<(usize, usize)>,
+///     #[readable(default = n)]
+///     vertices: usize,
+/// }
+///
+/// # let source = AutoSource::from("3 2\n1 2\n2 3");
+/// input! {
+/// #   from source,
+///     n: usize,
+///     m: usize,
+///     g: with GraphSpec { n, m },
+/// }
+///
+/// assert_eq!(g.edges, [(0, 1), (1, 2)]);
+/// assert_eq!(g.vertices, 3);
+/// ```
+#[proc_macro_attribute]
+pub fn derive_runtime_readable(attr: TokenStream, input: TokenStream) -> TokenStream {
+    derive_runtime_readable::main(attr, input)
+}
+
+/// Derives `Readable` for your own type, without changing the type definition.
+///
+/// Unlike `#[derive_readable]`, the member types are left unchanged, so they must be the actual
+/// types of the values.  A member is read by its own type, which must be `Readable` with the
+/// output type being itself, like `i64` or `String`.  To read it in a different way, choose the
+/// reader by `#[readable(as = Usize1)]`, whose output type must be the member type.  The other
+/// field attributes of `#[derive_readable]`, like `#[readable(len = n)]`, are also available, and
+/// `as` can be combined with `len` to choose the reader of the elements.
+///
+/// Enums with `#[tag = ...]` and generic types are supported as well, in the same way as
+/// `#[derive_readable]`.  Each type parameter `T` gets the bound `T: Readable<Output = T>`.
+///
+/// ```
+/// # extern crate proconio;
+/// # extern crate proconio_derive;
+/// use proconio::input;
+/// use proconio::marker::{Chars, Usize1};
+/// # use proconio::source::auto::AutoSource;
+/// use proconio_derive::Readable;
+///
+/// #[derive(Readable, Debug, Clone, PartialEq)]
+/// struct Edge {
+///     #[readable(as = Usize1)]
+///     from: usize,
+///     #[readable(as = Usize1)]
+///     to: usize,
+///     cost: i64,
+/// }
+///
+/// #[derive(Readable, Debug)]
+/// struct Case {
+///     n: usize,
+///     #[readable(as = Chars)]
+///     s: Vec<char>,
+///     #[readable(len = n - 1)]
+///     edges: Vec<Edge>,
+///     #[readable(len = n, as = Usize1)]
+///     p: Vec<usize>,
+/// }
+///
+/// # let source = AutoSource::from("3 abc\n1 2 10\n2 3 20\n3 1 2");
+/// input! {
+/// #   from source,
+///     case: Case,
+/// }
+///
+/// assert_eq!(case.s, ['a', 'b', 'c']);
+/// assert_eq!(case.edges[1], Edge { from: 1, to: 2, cost: 20 });
+/// assert_eq!(case.p, [2, 0, 1]);
+/// ```
+///
+/// The attribute form `#[derive_readable]` is kept for compatibility.
+#[proc_macro_derive(Readable, attributes(readable, tag))]
+pub fn derive_readable_derive(input: TokenStream) -> TokenStream {
+    derive_readable::derive(input)
+}
+
+/// Derives `Writable` for your own type.
+///
+/// This is the output counterpart of `#[derive_readable]`.  If every member of your struct
+/// implements `proconio::output::Writable`, the struct can be written by `output!` macro in the
+/// same format as the input.  Markers are undone while writing: a `Usize1` member is written as
+/// the value plus one, and a `Chars` member is written as a contiguous string.  Like
+/// `#[derive_readable]`, the member types are translated to the written value type, so a `Usize1`
+/// member is actually defined as `usize`.
+///
+/// The members are written in the declaration order.  They are separated by a space if both of
+/// the neighbours have `Writable::SEP` of a space, like `usize` or `Usize1`, and by a newline
+/// otherwise, so that a `Vec<i32>` or `Chars` member is placed in its own line even if it has only
+/// one element.  A struct with multiple members is placed in its own line when it is an element of
+/// `Vec`, and a struct with a single member is written just like the member.
+///
+/// Generic structs are supported like `#[derive_readable]`, with the bound `T: Writable` for each
+/// type parameter `T`.  Fields marked by `#[readable(skip)]` or `#[readable(default)]` are not
+/// written, since they are not in the input.  A field read by `#[readable(kind = "...")]` is
+/// written by the same kind, where `[T; n]` is written as `Vec<T>` and `[T]` is written with its
+/// length like `proconio::marker::LenPrefixed<T>`.  The kinds other than the types, arrays and
+/// tuples, and the fields read by `with`, cannot be written and are rejected.
+///
+/// Both `#[derive_readable]` and `#[derive_writable]` can be applied to the same struct in any
+/// order, so that a generator can build a value and write it as a valid input.  For generic
+/// structs, `T::Value` is then required to be the same as `T::Output`, which holds for all the
+/// markers in `proconio` such as `Usize1` and `Chars`.
+///
+/// ```
+/// # extern crate proconio;
+/// # extern crate proconio_derive;
+/// use proconio::marker::{Chars, Usize1};
+/// use proconio::output;
+/// use proconio_derive::{derive_readable, derive_writable};
+///
+/// #[derive_readable]
+/// #[derive_writable]
+/// struct Query {
+///     target: Usize1,
+///     name: Chars,
+/// }
+///
+/// let query = Query {
+///     target: 0,
+///     name: vec!['a', 'b', 'c'],
+/// };
+///
+/// let mut buf = Vec::new();
+/// output! {
+///     to &mut buf,
+///     query: Query,
+/// }
+/// assert_eq!(buf, b"1\nabc\n");
+/// ```
+#[proc_macro_attribute]
+pub fn derive_writable(attr: TokenStream, input: TokenStream) -> TokenStream {
+    derive_writable::main(attr, input)
+}
+
 /// Enables buffering for stdout.
 ///
-/// You cannot create a closure containing `print!` or `println!` in `#[fastout]` function.  This
-/// is because the closure cannot implement `Send` since `StdoutLock`, which is not a `Send`, is
-/// internally captured into the closure.  This causes a trait bound mismatch when used with
-/// function requiring its argument closure to be a `Send`, such as `std::thread::spawn()`.
+/// `print!` and `println!` in the annotated function write into a buffer shared by the whole
+/// program, which lives behind a global lock in `proconio::out`.  The macros don't capture any
+/// local state, so they can be used in closures and in other threads, such as a closure passed to
+/// `std::thread::spawn()`.
 ///
-/// ```compile_fail
-/// use proconio::fastout;
+/// ```
+/// use proconio_derive::fastout;
 ///
 /// use std::thread;
 ///
@@ -116,48 +419,165 @@ pub fn derive_readable(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
-/// It is too conservative to make all of such closures compilation error because it is actually no
-/// problem to use such a closure only inside a single thread.  However, since trait bound check is
-/// done after macro expansions, there is no way to check whther the closure is required to be a
-/// `Send` or not.  And the compiler error message for actual mismatch of a `Send` requirement is
-/// too confusing, pointing out codes you didn't write (macro-expanded codes) as an error position.
-/// In conclusion, for user-friendliness, all of them are prohibited for now.
+/// When the format string is `"{}"` or `{}`s joined by spaces like `"{} {} {}"`, the arguments are
+/// written by `proconio::out::FastWrite` without `core::fmt`, which is much faster for integers.
+/// Types not implementing `FastWrite` are written by `Display` as usual.  Use
+/// `proconio::out::fixed()` to print a floating point number quickly with a fixed precision.
 ///
-/// Internally this is the same with
+/// Other ways to write to stdout in the function are also redirected to the buffer:
+/// `std::print!` and `std::println!` are replaced with the shadowed `print!` and `println!`, and
+/// `std::io::stdout` is replaced with `proconio::out::stdout`, including the ones in macro
+/// arguments like `writeln!(std::io::stdout(), ...)`.  `io::stdout` is not replaced, since `io` may
+/// be a module other than `std::io`; write the full path `std::io::stdout` instead.  `stdout`
+/// imported by `use` inside the function is rejected with a compile error, since it cannot be
+/// replaced reliably.
+///
+/// With the `debug` option, `eprint!`, `eprintln!` and `dbg!` flush the buffer before writing to
+/// stderr, so that the output to stdout and stderr is kept in order.
+///
+/// ```
+/// use proconio_derive::fastout;
+/// use std::io::Write;
+///
+/// #[fastout(debug)]
+/// fn main() {
+///     std::println!("buffered");
+///     writeln!(std::io::stdout(), "buffered as well").unwrap();
+///     eprintln!("printed after the above");
+/// }
+/// ```
+///
+/// If you want to run the function in a thread having extended size of stack, use the `stack`
+/// option instead of spawning a thread by yourself.  The function is run in a new thread with the
+/// specified size of stack, and the buffer is flushed inside that thread.  The size is a
+/// number of bytes, or a string with a unit: `B`, `KiB`, `MiB`, `GiB`, `KB`, `MB` or `GB`.  The
+/// arguments and the return value of the function must be `Send` and `'static`.
+///
+/// ```
+/// use proconio_derive::fastout;
+///
+/// #[fastout(stack = "512MiB")]
+/// fn main() {
+///     fn dfs(n: u32) -> u32 {
+///         if n == 0 { 0 } else { dfs(n - 1) + 1 }
+///     }
+///
+///     println!("{}", dfs(100000));
+/// }
+/// ```
+///
+/// The buffer can be configured with the following options.  The configuration is applied while
+/// the function is running, and the previous one is restored when it exits.
+///
+/// - `capacity = 1 << 20`: the size of the buffer in bytes.  The default is 8 KiB.
+/// - `sink = stdout`, `sink = stderr` or `sink = file("out.txt")`: where the output is written to.
+///   The file is created, or truncated if it exists.  The default is `stdout`.
+/// - `flush = auto`, `flush = line` or `flush = manual`: when the output is written.  `auto`
+///   writes when the buffer becomes full, `line` writes after each output containing a newline,
+///   and `manual` writes only when the buffer is flushed explicitly, growing the buffer as needed.
+///   In any case, the output is written when the function exits.  The default is `auto`.
+///
+/// ```
+/// use proconio_derive::fastout;
+///
+/// #[fastout(capacity = 1 << 20, sink = stderr, flush = line)]
+/// fn main() {
+///     println!("to stderr");
+/// }
+/// ```
+///
+/// The buffer is flushed on every exit path of the function: the end of the body, an early
+/// `return`, `?` and a panic.  If flushing fails, for example because stdout is closed, the error
+/// is reported by a panic.  On a panic, the buffered output is written after the panic message.
+/// With the `flush_on_panic` option, the output printed before the panic is written first, even
+/// if the panic aborts the process.  See `proconio::out::flush_on_panic()`.
+///
+/// ```
+/// use proconio_derive::fastout;
 ///
+/// #[fastout(flush_on_panic)]
+/// fn main() {
+///     println!("partial output");
+/// }
 /// ```
-/// let __proconio_stdout = ::std::io::stdout();
-/// let mut __proconio_stdout = ::std::io::BufWriter::new(__proconio_stdout.lock());
 ///
+/// Internally this is the same with
+///
+/// ```
 /// #[allow(unused_macros)]
 /// macro_rules! print {
 ///     ($($tt:tt)*) => {{
-///         use std::io::Write as _;
-///         ::std::write!(__proconio_stdout, $($tt)*).unwrap();
+///         ::std::write!(::proconio::out::stdout(), $($tt)*).unwrap();
 ///     }};
 /// }
 ///
 /// #[allow(unused_macros)]
 /// macro_rules! println {
 ///     ($($tt:tt)*) => {{
-///         use std::io::Write as _;
-///         ::std::writeln!(__proconio_stdout, $($tt)*).unwrap();
+///         ::std::writeln!(::proconio::out::stdout(), $($tt)*).unwrap();
 ///     }};
 /// }
 ///
+/// // flushes the buffer even if the function exits by an early return, `?` or a panic.
+/// let __proconio_flush_on_drop = ::proconio::out::__flush_on_drop();
+///
 /// let __proconio_res = {
 ///     // Your code goes here
 /// };
-/// <::std::io::BufWriter<::std::io::StdoutLock> as ::std::io::Write>::flush(
-///     &mut __proconio_stdout
-/// ).unwrap();
-/// return __proconio_res;
+/// // flushes the buffer, and panics if it fails.
+/// ::std::mem::drop(__proconio_flush_on_drop);
+/// __proconio_res
 /// ```
 #[proc_macro_attribute]
 pub fn fastout(attr: TokenStream, input: TokenStream) -> TokenStream {
     fastout::main(attr, input)
 }
 
+/// Enables buffering for stdout, and optionally runs the body for each test case.
+///
+/// Without arguments, this is the same as `#[fastout]`.  The following arguments are available:
+///
+/// - `#[main(multi)]`: reads the number of test cases `t` from stdin first, and then runs the body
+///   `t` times.
+/// - `#[main(until_eof)]`: runs the body repeatedly while some tokens are left on stdin.  This is
+///   useful when the number of test cases is not given, like ICPC problems.
+///
+/// With either argument, the function must take no arguments and return nothing.  All test cases
+/// share one buffered stdout, which is flushed once after the last test case.  `return` in the
+/// body finishes only the current test case.  If the body panics, the index of the test case
+/// (1-indexed) is reported to stderr.
+///
+/// The body is expected to read its input by `input!` or `read_value!`.  `input_once!` cannot be
+/// used since the number of test cases is read by the line-by-line reader.
+///
+/// `#[main(stack = "512MiB")]` runs the function in a thread having the specified size of stack,
+/// in the same way as `#[fastout(stack = "512MiB")]`.  It can be combined with the other
+/// arguments, like `#[main(multi, stack = "512MiB")]`.  Similarly, `#[main(flush_on_panic)]` is the
+/// same as `#[fastout(flush_on_panic)]`, and `capacity`, `sink` and `flush` are the same as the
+/// ones of `#[fastout]`.
+///
+/// ```
+/// use proconio::input;
+/// use proconio_derive::main;
+///
+/// #[main(until_eof)]
+/// fn solve() {
+///     input! {
+///         a: i64,
+///         b: i64,
+///     }
+///     println!("{}", a + b);
+/// }
+/// #
+/// # fn main() {}
+/// ```
+///
+/// The restrictions of `#[fastout]` apply to this attribute as well.
+#[proc_macro_attribute]
+pub fn main(attr: TokenStream, input: TokenStream) -> TokenStream {
+    entry::main(attr, input)
+}
+
 fn compile_error_at(args: TokenStream2, start: Span2, end: Span2) -> Stmt {
     let start = start.unwrap();
     let end = end.unwrap();
@@ -0,0 +1,407 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Defines `RuntimeReadable` readers of graphs.
+//!
+//! - [`Directed`] and [`Undirected`] read `m` edges `u v` of a graph with `n` vertices.
+//! - [`TreeEdges`] reads `n - 1` edges of a tree.
+//! - [`ParentArray`] reads the parents `p_2 .. p_n` of a rooted tree, as the edges from the
+//!   parents to the children.
+//!
+//! The vertices are 1-indexed in the input by default, and converted to 0-indexed.  Each reader
+//! has the following options:
+//!
+//! - `.zero_indexed()` reads the vertices as 0-indexed.
+//! - `.weighted::<W>()` reads a weight of type `W` after each edge like `u v w`.
+//! - `.csr()` gives a compact [`Csr`] instead of `Vec<Vec<_>>`.
+//!
+//! The options can be given in any order, like `.csr().weighted::<W>()`.
+//! The adjacency list has `v` for an unweighted edge, or `(v, w)` for a weighted edge.  A vertex
+//! out of range panics, showing which edge it is numbered from 1 like `the vertex 4 of the edge 2`,
+//! or which parent it is like `the parent p_3`.
+//!
+//! ```
+//! # extern crate proconio;
+//! use proconio::graph::{Directed, TreeEdges, Undirected};
+//! use proconio::input;
+//! use proconio::source::auto::AutoSource;
+//!
+//! let source = AutoSource::from("3 2 1 2 2 3  3 2 1 2 5 2 3 7  3 1 2 1 3");
+//! input! {
+//!     from source,
+//!     n: usize,
+//!     m: usize,
+//!     g: with Directed::new(n, m),
+//!     n2: usize,
+//!     m2: usize,
+//!     weighted: with Undirected::new(n2, m2).weighted::<i64>(),
+//!     n3: usize,
+//!     tree: with TreeEdges::new(n3).csr(),
+//! }
+//!
+//! assert_eq!(g, [vec![1], vec![2], vec![]]);
+//! assert_eq!(weighted, [vec![(1, 5)], vec![(0, 5), (2, 7)], vec![(1, 7)]]);
+//! assert_eq!(tree[0], [1, 2]);
+//! ```
+
+use crate::source::{Readable, RuntimeReadable, Source};
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::ops::Index;
+
+/// A graph in the compressed sparse row format: the adjacency list of all the vertices is stored
+/// in a single array.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Csr<E> {
+    start: Vec<usize>,
+    adjacent: Vec<E>,
+}
+
+impl<E> Csr<E> {
+    /// Creates a graph with `n` vertices from the pairs of a vertex and its adjacent element.  The
+    /// elements of each vertex keep their order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a vertex is not less than `n`.
+    pub fn new(n: usize, arcs: impl IntoIterator<Item = (usize, E)>) -> Csr<E> {
+        let mut arcs: Vec<_> = arcs.into_iter().collect();
+        if let Some(&(u, _)) = arcs.iter().find(|&&(u, _)| u >= n) {
+            panic!("the vertex {} is out of the graph of {} vertices", u, n);
+        }
+
+        // the stable sort keeps the order of the adjacent elements of each vertex
+        arcs.sort_by_key(|&(u, _)| u);
+        let mut start = vec![0; n + 1];
+        for &(u, _) in &arcs {
+            start[u + 1] += 1;
+        }
+        for v in 0..n {
+            start[v + 1] += start[v];
+        }
+
+        Csr {
+            start,
+            adjacent: arcs.into_iter().map(|(_, e)| e).collect(),
+        }
+    }
+
+    /// The number of the vertices.
+    pub fn len(&self) -> usize {
+        self.start.len() - 1
+    }
+
+    /// Checks if the graph has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of the adjacent elements of all the vertices.  An undirected edge is counted
+    /// twice.
+    pub fn num_arcs(&self) -> usize {
+        self.adjacent.len()
+    }
+
+    /// Iterates over the adjacent elements of each vertex.
+    pub fn iter(&self) -> impl Iterator<Item = &[E]> {
+        (0..self.len()).map(move |v| &self[v])
+    }
+}
+
+impl<E> Index<usize> for Csr<E> {
+    type Output = [E];
+
+    fn index(&self, v: usize) -> &[E] {
+        &self.adjacent[self.start[v]..self.start[v + 1]]
+    }
+}
+
+/// The weight of the unweighted edges, which reads nothing.
+pub enum Unweighted {}
+
+impl Readable for Unweighted {
+    type Output = ();
+    fn read<R: BufRead, S: Source<R>>(_source: &mut S) {}
+}
+
+/// A graph representation which the readers in this module can build.
+pub trait FromEdges {
+    /// The weight read after each edge, or `Unweighted`.
+    type Weight: Readable;
+
+    /// Builds the graph with `n` vertices from the edges `(u, v, w)`.
+    fn from_edges(
+        n: usize,
+        edges: Vec<(usize, usize, <Self::Weight as Readable>::Output)>,
+        directed: bool,
+    ) -> Self;
+}
+
+/// Converts the edges into the pairs of a vertex and its adjacent element.
+fn arcs<W: Clone, E>(
+    edges: Vec<(usize, usize, W)>,
+    directed: bool,
+    adjacent: impl Fn(usize, W) -> E,
+) -> Vec<(usize, E)> {
+    let mut res = Vec::with_capacity(if directed { 1 } else { 2 } * edges.len());
+    for (u, v, w) in edges {
+        if !directed {
+            res.push((v, adjacent(u, w.clone())));
+        }
+        res.push((u, adjacent(v, w)));
+    }
+    res
+}
+
+fn adjacency_list<E>(n: usize, arcs: Vec<(usize, E)>) -> Vec<Vec<E>> {
+    let mut res: Vec<Vec<E>> = (0..n).map(|_| Vec::new()).collect();
+    for (u, e) in arcs {
+        res[u].push(e);
+    }
+    res
+}
+
+impl FromEdges for Vec<Vec<usize>> {
+    type Weight = Unweighted;
+    fn from_edges(n: usize, edges: Vec<(usize, usize, ())>, directed: bool) -> Self {
+        adjacency_list(n, arcs(edges, directed, |v, ()| v))
+    }
+}
+
+impl<W: Readable<Output = W> + Clone> FromEdges for Vec<Vec<(usize, W)>> {
+    type Weight = W;
+    fn from_edges(n: usize, edges: Vec<(usize, usize, W)>, directed: bool) -> Self {
+        adjacency_list(n, arcs(edges, directed, |v, w| (v, w)))
+    }
+}
+
+impl FromEdges for Csr<usize> {
+    type Weight = Unweighted;
+    fn from_edges(n: usize, edges: Vec<(usize, usize, ())>, directed: bool) -> Self {
+        Csr::new(n, arcs(edges, directed, |v, ()| v))
+    }
+}
+
+impl<W: Readable<Output = W> + Clone> FromEdges for Csr<(usize, W)> {
+    type Weight = W;
+    fn from_edges(n: usize, edges: Vec<(usize, usize, W)>, directed: bool) -> Self {
+        Csr::new(n, arcs(edges, directed, |v, w| (v, w)))
+    }
+}
+
+/// Reads a vertex, converting it to 0-indexed.  If it is out of range, panics with the message
+/// naming the vertex by `name`, which is given the vertex read.
+fn read_vertex<R: BufRead, S: Source<R>>(
+    source: &mut S,
+    n: usize,
+    offset: usize,
+    name: impl Fn(usize) -> String,
+) -> usize {
+    let v = usize::read(source);
+    match v.checked_sub(offset) {
+        Some(res) if res < n => res,
+        _ => panic!("{} is out of range {}..{}", name(v), offset, n + offset),
+    }
+}
+
+/// Reads `m` edges `u v w`.
+fn read_edges<W: Readable, R: BufRead, S: Source<R>>(
+    source: &mut S,
+    n: usize,
+    m: usize,
+    offset: usize,
+) -> Vec<(usize, usize, W::Output)> {
+    (0..m)
+        .map(|idx| {
+            // the edges are numbered from 1 like the rows of the grids
+            let name = |v| format!("the vertex {} of the edge {}", v, idx + 1);
+            let u = read_vertex(source, n, offset, name);
+            let v = read_vertex(source, n, offset, name);
+            (u, v, W::read(source))
+        })
+        .collect()
+}
+
+macro_rules! impl_reader_options {
+    ($name:ident) => {
+        impl<G> $name<G> {
+            /// Reads the vertices as 0-indexed, instead of 1-indexed.
+            pub fn zero_indexed(self) -> Self {
+                $name { offset: 0, ..self }
+            }
+        }
+
+        impl $name {
+            /// Reads a weight of type `W` after each edge.
+            pub fn weighted<W>(self) -> $name<Vec<Vec<(usize, W)>>> {
+                $name {
+                    n: self.n,
+                    m: self.m,
+                    offset: self.offset,
+                    _marker: PhantomData,
+                }
+            }
+        }
+
+        impl $name<Csr<usize>> {
+            /// Reads a weight of type `W` after each edge.
+            pub fn weighted<W>(self) -> $name<Csr<(usize, W)>> {
+                $name {
+                    n: self.n,
+                    m: self.m,
+                    offset: self.offset,
+                    _marker: PhantomData,
+                }
+            }
+        }
+
+        impl<E> $name<Vec<Vec<E>>> {
+            /// Gives the graph as [`Csr`] instead of `Vec<Vec<_>>`.
+            pub fn csr(self) -> $name<Csr<E>> {
+                $name {
+                    n: self.n,
+                    m: self.m,
+                    offset: self.offset,
+                    _marker: PhantomData,
+                }
+            }
+        }
+    };
+}
+
+/// Reads a directed graph with `n` vertices and `m` edges `u v`, each of which is from `u` to `v`.
+pub struct Directed<G = Vec<Vec<usize>>> {
+    n: usize,
+    m: usize,
+    offset: usize,
+    _marker: PhantomData<fn() -> G>,
+}
+
+impl Directed {
+    /// Creates a reader of `m` edges of a graph with `n` vertices, giving `Vec<Vec<usize>>`.
+    pub fn new(n: usize, m: usize) -> Self {
+        Directed {
+            n,
+            m,
+            offset: 1,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl_reader_options!(Directed);
+
+impl<G: FromEdges> RuntimeReadable for Directed<G> {
+    type Output = G;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> G {
+        let edges = read_edges::<G::Weight, _, _>(source, self.n, self.m, self.offset);
+        G::from_edges(self.n, edges, true)
+    }
+}
+
+/// Reads an undirected graph with `n` vertices and `m` edges `u v`.
+pub struct Undirected<G = Vec<Vec<usize>>> {
+    n: usize,
+    m: usize,
+    offset: usize,
+    _marker: PhantomData<fn() -> G>,
+}
+
+impl Undirected {
+    /// Creates a reader of `m` edges of a graph with `n` vertices, giving `Vec<Vec<usize>>`.  Each
+    /// edge is added to the adjacency lists of both ends.
+    pub fn new(n: usize, m: usize) -> Self {
+        Undirected {
+            n,
+            m,
+            offset: 1,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl_reader_options!(Undirected);
+
+impl<G: FromEdges> RuntimeReadable for Undirected<G> {
+    type Output = G;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> G {
+        let edges = read_edges::<G::Weight, _, _>(source, self.n, self.m, self.offset);
+        G::from_edges(self.n, edges, false)
+    }
+}
+
+/// Reads a tree with `n` vertices, given by `n - 1` undirected edges `u v`.
+pub struct TreeEdges<G = Vec<Vec<usize>>> {
+    n: usize,
+    m: usize,
+    offset: usize,
+    _marker: PhantomData<fn() -> G>,
+}
+
+impl TreeEdges {
+    /// Creates a reader of `n - 1` edges of a tree with `n` vertices, giving `Vec<Vec<usize>>`.
+    pub fn new(n: usize) -> Self {
+        TreeEdges {
+            n,
+            m: n.saturating_sub(1),
+            offset: 1,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl_reader_options!(TreeEdges);
+
+impl<G: FromEdges> RuntimeReadable for TreeEdges<G> {
+    type Output = G;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> G {
+        let edges = read_edges::<G::Weight, _, _>(source, self.n, self.m, self.offset);
+        G::from_edges(self.n, edges, false)
+    }
+}
+
+/// Reads a rooted tree with `n` vertices, given by the parents `p_2 .. p_n` of the vertices
+/// except the root.  The graph has the edges from the parents to the children, so the adjacency
+/// list of each vertex is its children.
+///
+/// With `weighted`, each parent is followed by the weight of the edge like `p_i w_i`.
+pub struct ParentArray<G = Vec<Vec<usize>>> {
+    n: usize,
+    m: usize,
+    offset: usize,
+    _marker: PhantomData<fn() -> G>,
+}
+
+impl ParentArray {
+    /// Creates a reader of `n - 1` parents of a rooted tree with `n` vertices, giving
+    /// `Vec<Vec<usize>>` of the children.
+    pub fn new(n: usize) -> Self {
+        ParentArray {
+            n,
+            m: n.saturating_sub(1),
+            offset: 1,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl_reader_options!(ParentArray);
+
+impl<G: FromEdges> RuntimeReadable for ParentArray<G> {
+    type Output = G;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> G {
+        let edges = (0..self.m)
+            .map(|idx| {
+                // the parent of the vertex `idx + 1` is given as `p_{idx + 1 + offset}`
+                let name = |p| format!("the parent p_{} = {}", idx + 1 + self.offset, p);
+                let parent = read_vertex(source, self.n, self.offset, name);
+                (parent, idx + 1, G::Weight::read(source))
+            })
+            .collect();
+        G::from_edges(self.n, edges, true)
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Defines `Grid<T>`, a 2-D array stored contiguously in row-major order.
+//!
+//! A grid is read by `[[T; w]; h] as Matrix` in `input!`, or by [`Matrix`](crate::marker::Matrix)
+//! with `with`.  Unlike `Vec<Vec<T>>`, all the cells are in a single allocation.
+//!
+//! ```
+//! # extern crate proconio;
+//! use proconio::input;
+//! use proconio::source::auto::AutoSource;
+//!
+//! let source = AutoSource::from("2 3 1 2 3 4 5 6");
+//! input! {
+//!     from source,
+//!     h: usize,
+//!     w: usize,
+//!     a: [[i32; w]; h] as Matrix,
+//! }
+//!
+//! assert_eq!(a[(1, 2)], 6);
+//! assert_eq!(a.row(0), [1, 2, 3]);
+//! assert_eq!(a.get(-1, 0), None);
+//! assert_eq!(a.neighbors4(0, 0).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+//! ```
+
+use std::convert::TryInto;
+use std::ops::{Index, IndexMut};
+
+/// A 2-D array of `height` rows and `width` columns, stored in row-major order.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Grid<T> {
+    height: usize,
+    width: usize,
+    data: Vec<T>,
+}
+
+/// The directions to the 4 adjacent cells: down, right, up and left.
+const DIRECTIONS4: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// The directions to the 8 adjacent cells including the diagonal ones.
+const DIRECTIONS8: [(isize, isize); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+impl<T> Grid<T> {
+    /// Creates a grid from the cells in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data.len()` is not `height * width`.
+    pub fn from_vec(height: usize, width: usize, data: Vec<T>) -> Grid<T> {
+        assert!(
+            Some(data.len()) == height.checked_mul(width),
+            "the number of cells {} differs from the size of the grid {}x{}",
+            data.len(),
+            height,
+            width,
+        );
+
+        Grid {
+            height,
+            width,
+            data,
+        }
+    }
+
+    /// Creates a grid by calling `f(i, j)` for each cell in row-major order.
+    pub fn from_fn(height: usize, width: usize, mut f: impl FnMut(usize, usize) -> T) -> Grid<T> {
+        let data = (0..height)
+            .flat_map(|i| (0..width).map(move |j| (i, j)))
+            .map(|(i, j)| f(i, j))
+            .collect();
+
+        Grid::from_vec(height, width, data)
+    }
+
+    /// The number of the rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of the columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the cell at the `i`-th row and the `j`-th column, or `None` if it is out of the grid.
+    ///
+    /// The indices can be signed, so that `grid.get(i as isize - 1, j as isize)` is `None` for
+    /// the first row instead of overflowing.
+    pub fn get<I: TryInto<usize>>(&self, i: I, j: I) -> Option<&T> {
+        let (i, j) = self.position(i, j)?;
+        Some(&self.data[i * self.width + j])
+    }
+
+    /// Mutable version of [`get`](Grid::get).
+    pub fn get_mut<I: TryInto<usize>>(&mut self, i: I, j: I) -> Option<&mut T> {
+        let (i, j) = self.position(i, j)?;
+        Some(&mut self.data[i * self.width + j])
+    }
+
+    /// Gets the position moved from `(i, j)` by `(di, dj)`, or `None` if it is out of the grid.
+    pub fn offset(
+        &self,
+        (i, j): (usize, usize),
+        (di, dj): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        self.position(i.checked_add_signed(di)?, j.checked_add_signed(dj)?)
+    }
+
+    /// The row at the index `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of the grid.
+    pub fn row(&self, i: usize) -> &[T] {
+        assert!(i < self.height, "the row {} is out of the grid", i);
+        &self.data[i * self.width..(i + 1) * self.width]
+    }
+
+    /// Mutable version of [`row`](Grid::row).
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        assert!(i < self.height, "the row {} is out of the grid", i);
+        &mut self.data[i * self.width..(i + 1) * self.width]
+    }
+
+    /// Iterates over the rows.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |i| self.row(i))
+    }
+
+    /// Iterates over the cells in row-major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Iterates over the positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    /// Iterates over the positions of the up to 4 cells adjacent to `(i, j)` in the grid.
+    pub fn neighbors4(&self, i: usize, j: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS4
+            .iter()
+            .filter_map(move |&d| self.offset((i, j), d))
+    }
+
+    /// Iterates over the positions of the up to 8 cells adjacent to `(i, j)` in the grid,
+    /// including the diagonal ones.
+    pub fn neighbors8(&self, i: usize, j: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS8
+            .iter()
+            .filter_map(move |&d| self.offset((i, j), d))
+    }
+
+    /// The cells in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Converts into the cells in row-major order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Creates a grid of the same size by applying `f` to each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.height, self.width, self.data.iter().map(f).collect())
+    }
+
+    /// Finds the position of the first cell equal to `value` in row-major order, like the start
+    /// `S` of a maze.
+    pub fn find(&self, value: &T) -> Option<(usize, usize)>
+    where
+        T: PartialEq,
+    {
+        let idx = self.data.iter().position(|cell| cell == value)?;
+        Some((idx / self.width, idx % self.width))
+    }
+
+    /// Finds the positions of all the cells equal to `value` in row-major order.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = (usize, usize)> + 'a
+    where
+        T: PartialEq,
+    {
+        self.positions()
+            .filter(move |&(i, j)| self[(i, j)] == *value)
+    }
+
+    fn position<I: TryInto<usize>>(&self, i: I, j: I) -> Option<(usize, usize)> {
+        let i = i.try_into().ok()?;
+        let j = j.try_into().ok()?;
+        if i < self.height && j < self.width {
+            Some((i, j))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        self.get(i, j).unwrap_or_else(|| {
+            panic!(
+                "the position ({}, {}) is out of the grid {}x{}",
+                i, j, self.height, self.width
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        self.get_mut(i, j).unwrap_or_else(|| {
+            panic!(
+                "the position ({}, {}) is out of the grid {}x{}",
+                i, j, height, width
+            )
+        })
+    }
+}
@@ -176,6 +176,81 @@
 //! # );
 //! ```
 //!
+//! An array can be collected into any collection implementing `FromIterator` by `as`, and
+//! key-value pairs in braces are collected into a `HashMap`, or the map given by `as`.
+//!
+//! ```
+//! # extern crate proconio;
+//! # use proconio::source::auto::AutoSource;
+//! use proconio::input;
+//! use std::collections::{BTreeMap, BTreeSet, HashMap};
+//! # let source = AutoSource::from("3 1 2 1 alice 3 bob 5 2 x 1 y 2");
+//!
+//! input! {
+//! #   from source,
+//!     n: usize,
+//!     s: [i64; n] as BTreeSet,
+//!     scores: {(String, i32); 2},
+//!     index: {(char, usize)} as BTreeMap,
+//! }
+//!
+//! // if you enter "3 1 2 1 alice 3 bob 5 2 x 1 y 2", they are like this.
+//! assert_eq!(s, BTreeSet::from([1, 2]));
+//! assert_eq!(scores["bob"], 5);
+//! assert_eq!(index, BTreeMap::from([('x', 1), ('y', 2)]));
+//! ```
+//!
+//! The braces panic on a duplicate key by default, since it usually means the input format is
+//! misread.  If the input may contain the same key more than once, add `keep_first` or
+//! `keep_last` right after the braces to keep the first or the last value of the key instead.
+//!
+//! ```
+//! # extern crate proconio;
+//! # use proconio::source::auto::AutoSource;
+//! use proconio::input;
+//! use std::collections::BTreeMap;
+//! # let source = AutoSource::from("a 1 b 2 a 3 a 1 b 2 a 3");
+//!
+//! input! {
+//! #   from source,
+//!     first: {(char, i32); 3} keep_first,
+//!     last: {(char, i32); 3} keep_last as BTreeMap,
+//! }
+//!
+//! assert_eq!(first[&'a'], 1);
+//! assert_eq!(last[&'a'], 3);
+//! ```
+//!
+//! A matrix `[[T; w]; h] as Matrix` is read into a [`Grid<T>`](grid::Grid) instead of
+//! `Vec<Vec<T>>`, which stores all the cells contiguously in a single allocation.  For the grids of
+//! characters, `maze: with CharGrid::new(h)` reads `h` rows of `Chars` into a `Grid<char>`, and
+//! [`ByteGrid`](marker::ByteGrid) and [`BoolGrid`](marker::BoolGrid) are also available.
+//! Likewise, the [`graph`] module has the readers of graphs like `g: with Undirected::new(n, m)`,
+//! building adjacency lists from the edges.
+//!
+//! An array of tuples followed by `unzip` is read as a tuple of arrays, one for each element of the
+//! tuple.  A matrix followed by `transpose` is transposed, which is useful when the input is given
+//! column by column.
+//!
+//! ```
+//! # extern crate proconio;
+//! # use proconio::source::auto::AutoSource;
+//! use proconio::input;
+//! # let source = AutoSource::from("3 1 10 2 20 3 30 1 2 3 4 5 6");
+//!
+//! input! {
+//! #   from source,
+//!     n: usize,
+//!     (a, b): [(i64, i64); n] unzip,
+//!     c: [[i32; 2]; 3] transpose,
+//! }
+//!
+//! // if you enter "3 1 10 2 20 3 30 1 2 3 4 5 6", they are like this.
+//! assert_eq!(a, [1, 2, 3]);
+//! assert_eq!(b, [10, 20, 30]);
+//! assert_eq!(c, [[1, 3, 5], [2, 4, 6]]);
+//! ```
+//!
 //! You can use `input!` macro multiple times.  For the second time, `input!` macro reads rest of
 //! input.  It works even if the first input stops at the middle of a line.  The subsequent reads
 //! will be started at the rest of the line.  This may be helpful for problems where multiple
@@ -205,7 +280,9 @@
 //!
 //! In addition to `Chars` and `Bytes`, `Usize1` and `Isize1` are also special types.  They are
 //! read as `usize` and `isize` respectively, but the read value is decremented.  It enables us to
-//! automatically convert 1-indexed vertices numbers to 0-indexed array indices.
+//! automatically convert 1-indexed vertices numbers to 0-indexed array indices.  They are aliases
+//! of [`Offset<T, K>`](marker::Offset), which subtracts `K` from any integer type `T`, and there
+//! are more aliases like `U32_1` and `I64_1`.
 //!
 //! ```
 //! # extern crate proconio;
@@ -234,9 +311,40 @@
 //! `Usize1` is implementing `Readable` trait, and there the type of read value is defined as
 //! `usize`.  You can implement `Readable` for your own type to read values in customized way.
 //!
+//! Tuples up to 12 elements and fixed-size arrays `[T; N]` are also `Readable`, reading their
+//! elements in order, so `(usize, Usize1)` can be a member of `#[derive_readable]` or a type
+//! argument.  Note that `[T; n]` inside `input!` still reads a `Vec`.  Likewise, `[T]` has its
+//! type-level version [`LenPrefixed<T>`](marker::LenPrefixed), reading a length followed by that
+//! many values of `T`.  The std wrappers `Reverse<T>`, `Wrapping<T>`, `Saturating<T>`, `Rc<T>`
+//! and `Arc<T>` read the inner value, the `NonZero*` integers panic on zero, and
+//! [`Optional<T>`](marker::Optional) reads `-1` as `None`, so that `[Reverse<i64>; n]` or
+//! `[Optional<Usize1>; n]` is read directly.  `Box<T>` reads the inner value only if it is a
+//! single token type implementing [`FromToken`](source::FromToken) like `Box<i64>`: the other
+//! inner types like `Box<(i32, i32)>` or `Box<Usize1>` are not `Readable` and rejected at compile
+//! time, so read them by `Rc<T>` or read `T` and box it yourself.
+//!
+//! The primitives and `String` are read by [`FromToken`](source::FromToken), which parses the
+//! integers faster than `FromStr`.  Other types implementing `FromStr`, like `Ipv4Addr` or a big
+//! integer from another crate, are read by `FromStr` in `input!` and `read_value!` as before.
+//! They are not `Readable` by themselves, though: where a `Readable` type is required, like a
+//! member of `#[derive_readable]` or `Reverse<T>`, read them by [`Parse<T>`](marker::Parse) like
+//! `Reverse<Parse<BigInt>>`.  For your own types, implement `FromToken`, which makes them
+//! `Readable`, or implement `Readable` directly, which is now allowed even if they implement
+//! `FromStr`.  See [the migration note](source::FromToken#migration-from-fromstr) for details.
+//!
 //! Finally, you can make your own types `Readable` using `#[derive_readable]` attribute.  Types
 //! used in the struct are automatically translated to their output types, so a member declared as
-//! `Usize1` has type `usize` as real struct.
+//! `Usize1` has type `usize` as real struct.  Enums are also supported: each variant is selected by
+//! the tag token read before its fields, which is given by `#[tag = 1]` or `#[tag = "add"]` and
+//! defaults to the 1-based position of the variant.  Field attributes like `#[readable(len = n)]`
+//! read a member depending on the members read before it.
+//!
+//! `#[derive(Readable)]` is also available.  It leaves the struct unchanged, so the members have
+//! the actual types, and a marker is given by a field attribute like `#[readable(as = Usize1)]`.
+//!
+//! For types depending on values known at runtime, `#[derive_runtime_readable(params(n: usize))]`
+//! generates a struct `<Name>Spec { n }` implementing `RuntimeReadable`, used like
+//! `g: with GraphSpec { n }` in `input!`.
 //!
 //! **Note:** Using `#[derive_readable]` requires `derive` feature enabled.  To do so, open your
 //! Cargo.toml and modify the line of proconio from:
@@ -367,189 +475,212 @@
 //! # }
 //! ```
 //!
-//! ## Closures having `print!` or `println!` in `#[fastout]` function
-//!
-//! You cannot create a closure containing `print!` or `println!` in `#[fastout]` function.  This
-//! is because the closure becomes thread-unsafe since the closure refers the unlocked stdout
-//! introduced by `#[fastout]` attribute.  If this were not prohibited, an invalid usage of such a
-//! closure would produce a very complex error messages.  For example, `std::thread::spawn()`,
-//! which requires its argument closure to be thread-safe, causes a confusing error.
-//!
-//! Yes, it is too conservative to make all of such closures compilation error because it is
-//! actually no problem to use such a closure only inside a single thread.  This is related to a
-//! limitation in `#[fastout]` implementation.
+//! ## Closures and threads in `#[fastout]` function
 //!
-//! For more technical details, see documentation for `#[fastout]` in `proconio-derive`.
+//! `print!` and `println!` in a `#[fastout]` function write into a buffer shared by the whole
+//! program, so you can use them in closures, iterator adapters and spawned threads as well.
 //!
-//! ### How to resolve this error
-//!
-//! Consider you want to run this code:
-//!
-//! ```compile_fail
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
 //! use proconio::fastout;
 //!
 //! #[fastout]
 //! fn main() {
+//!     (1..=3).for_each(|x| println!("{}", x));
+//!
 //!     let thread = std::thread::spawn(|| {
-//!         let x = 3;
-//!         let y = x * x;
-//!         println!("{}", y);
+//!         println!("hello from another thread");
 //!     });
-//!
 //!     thread.join().unwrap();
 //! }
+//! # }
 //! ```
 //!
-//! You will get an error like below.
-//!
-//! ```text
-//! error: Closures in a #[fastout] function cannot contain `print!` or `println!` macro
-//!
-//! note: If you want to run your entire logic in a thread having extended size of stack, you can
-//! define a new function instead.  See documentation (https://.....) for more details.
-//!
-//! note: This is because if you use this closure with `std::thread::spawn()` or any other
-//! functions requiring `Send` for an argument closure, the compiler emits an error about thread
-//! unsafety for our internal implementations.  If you are using the closure just in a single
-//! thread, it's actually no problem, but we cannot check the trait bounds at the macro-expansion
-//! time.  So for now, all closures having `print!` or `println!` is prohibited regardless of the
-//! `Send` requirements.
-//!  --> src/test.rs:10:9
-//!    |
-//! 10 |         println!("{}", y);
-//!    |         ^^^^^^^
-//! ```
-//!
-//! If your `print!` is relying on the calculation in the thread, you can instead return the result
-//! from the thread.
+//! Each `print!` or `println!` locks the buffer while it writes, so the output of one call is never
+//! mixed with the output from another thread.  The output of different threads appears in the
+//! order the macros are called.
+//!
+//! The buffer is flushed on every exit path of the `#[fastout]` function, including an early
+//! `return`, `?` and a panic.  An I/O error while flushing is reported by a panic.  When the
+//! function panics, the buffered output is written after the panic message by default.  If you
+//! want the output printed before the panic to come first, for example to see how far your program
+//! went, use `#[fastout(flush_on_panic)]`.  This writes the output even if the panic aborts the
+//! process.
+//!
+//! The buffer can be configured by the options of `#[fastout]`: `capacity` for the size of the
+//! buffer, `sink` for the destination (`stdout`, `stderr` or `file("out.txt")`), and `flush` for
+//! when the output is written (`auto`, `line` or `manual`).  For example,
+//! `#[fastout(flush = line)]` is useful to keep the order with the messages printed to stderr
+//! while debugging.
+//!
+//! If the format string is `"{}"` or `{}`s joined by spaces like `"{} {}"`, `print!` and
+//! `println!` in a `#[fastout]` function write integers and strings without `core::fmt`, which
+//! makes printing a large number of integers much faster.  To print a floating point number with a
+//! fixed precision in the same way, use [`out::fixed()`], like `println!("{}", fixed(x, 10))`.
+//!
+//! `std::println!`, `writeln!(std::io::stdout(), ...)` and `std::io::stdout().lock()` in a
+//! `#[fastout]` function are also redirected to the buffer.  Only the full path `std::io::stdout`
+//! is redirected; `io::stdout` is left as is.  With `#[fastout(debug)]`, `eprint!`, `eprintln!`
+//! and `dbg!` flush the buffer before writing, so your debug output is kept in order with the
+//! output to stdout.
+//!
+//! A value is formatted before the buffer is locked, so a `Display` implementation of a value
+//! printed by `print!` or `println!` can call `print!` or `println!` by itself.  Its output comes
+//! before the value.
+//!
+//! If you want to run your entire logic in a thread having extended size of stack, for example for
+//! a deep DFS, use the `stack` option of `#[fastout]`.  It runs the function in a new thread with
+//! the specified size of stack.
 //!
 //! ```
 //! # #[cfg(feature = "derive")]
 //! # {
 //! use proconio::fastout;
 //!
-//! #[fastout]
+//! #[fastout(stack = "512MiB")]
 //! fn main() {
-//!     let thread = std::thread::spawn(|| {
-//!         let x = 3;
-//!         x * x
-//!     });
+//!     fn dfs(n: u32) -> u32 {
+//!         if n == 0 { 0 } else { dfs(n - 1) + 1 }
+//!     }
 //!
-//!     let y = thread.join().unwrap();
-//! #   assert_eq!(y, 9);
-//!     println!("{}", y);
+//!     println!("{}", dfs(100000));
 //! }
 //! # }
 //! ```
 //!
-//! If you are doing so complex job that it's too difficult to returning the results from your
-//! closure...
+//! The size is a number of bytes, or a string with a unit such as `"64MiB"` or `"1GiB"`.  The same
+//! option is available for `#[proconio::main]`, like `#[proconio::main(multi, stack = "512MiB")]`.
 //!
-//! ```compile_fail
-//! use proconio::fastout;
+//! ## Issues of printing order
 //!
-//! # fn some_function(_: String) -> impl Iterator<Item = String> { vec!["hello".to_string(), "world".to_string()].into_iter() }
-//! # fn some_proc(x: &str) -> &str { x }
+//! `#[fastout]` enables buffering to stdout, so if you print something in other functions between
+//! two prints in main, the order of printing may differ.  In other words, the below example
 //!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! # use proconio::fastout;
+//! fn foo() { println!("between"); }
 //! #[fastout]
 //! fn main() {
-//!     let context = "some context".to_string();
-//!     let thread = std::thread::spawn(move || {
-//!         // Use many println! and the order is very important
-//!         // It's possible to aggregate the result and print it later, but it's not easy to read
-//!         // and seems ugly.
-//!         println!("this is header.");
-//!         for (i, item) in some_function(context).enumerate() {
-//!             print!("Item #{}: ", i);
-//!             print!("{}", some_proc(&item));
-//!             println!("({})", item);
-//!         }
-//!     });
-//!
-//!     thread.join().unwrap();
+//!     println!("hello");
+//!     foo();
+//!     println!("world");
 //! }
+//! # }
+//! ```
+//!
+//! *likely* prints like
+//!
+//! ```text
+//! between
+//! hello
+//! world
 //! ```
 //!
-//! ...you can use a function instead.
+//! To keep the order, import `print!` and `println!` from [`proconio::out`](out) in the other
+//! functions.  They write into the same buffer as `#[fastout]`.
 //!
 //! ```
 //! # #[cfg(feature = "derive")]
 //! # {
-//! use proconio::fastout;
-//!
-//! # fn some_function(_: String) -> impl Iterator<Item = String> { vec!["hello".to_string(), "world".to_string()].into_iter() }
-//! # fn some_proc(x: &str) -> &str { x }
+//! # use proconio::fastout;
+//! use proconio::out::println;
 //!
-//! // You can add #[fastout] here
+//! fn foo() { println!("between"); }
 //! #[fastout]
-//! fn process(context: String) {
-//!     // It's completely OK since this #[fastout] is a thing inside `process()`
-//!     println!("this is header.");
-//!     for (i, item) in some_function(context).enumerate() {
-//!         print!("Item #{}: ", i);
-//!         print!("{}", some_proc(&item));
-//!         println!("({})", item);
-//!     }
-//! }
-//!
-//! // You must not add #[fastout] here!  It causes deadlock.
-//! // #[fastout]
 //! fn main() {
-//!     let context = "some context".to_string();
-//!     let thread = std::thread::spawn(move || process(context));
-//!     thread.join().unwrap();
+//!     println!("hello");
+//!     foo();
+//!     println!("world");
 //! }
 //! # }
 //! ```
 //!
-//! **Important Note:** If you *spawn a new thread* which runs another function annotated with
-//! `#[fastout]`, you must not add `#[fastout]` to the caller.  If you add `#[fastout]` in caller
-//! too, then the caller has the lock for the stdout, and so callee cannot acquire the lock forever
-//! --- deadlock.  This is not the case when the caller and callee is executed in the same thread,
-//! since the lock of stdout is reentrant.  We cannot warn about this kind of deadlock since we
-//! don't know annotations attached to the function to be called.  (In the above example, we can't
-//! know whether the function `process()` has `#[fastout]` attribute or not.)
+//! The buffer of `proconio::out` is also flushed when the program exits, so you can use these
+//! macros without `#[fastout]` at all.  See [the module documentation](out) for details.
 //!
-//! If your code is so complex that you cannot avoid deadlock, you should give up using
-//! `#[fastout]` and simply use `println!` or manually handle your stdout in usual Rust way.
+//! # `#[proconio::main]`
 //!
-//! ## Issues of printing order
+//! Many problems give the number of test cases `t` first, followed by `t` independent test cases.
+//! `#[proconio::main(multi)]` reads `t` and runs the annotated function `t` times.  Each run
+//! solves one test case.  Like `#[fastout]`, `print!` and `println!` are buffered, and the buffer is
+//! shared by all the test cases.  If a test case panics, its index is reported to stderr.
 //!
-//! `#[fastout]` enables buffering to stdout, so if you print something in other functions between
-//! two prints in main, the order of printing may differ.  In other words, the below example
+//! **Note:** Using `#[proconio::main]` requires `derive` feature enabled.
 //!
 //! ```
 //! # #[cfg(feature = "derive")]
 //! # {
-//! # use proconio::fastout;
-//! fn foo() { println!("between"); }
-//! #[fastout]
-//! fn main() {
-//!     println!("hello");
-//!     foo();
-//!     println!("world");
+//! use proconio::input;
+//!
+//! #[proconio::main(multi)]
+//! fn solve() {
+//!     input! {
+//!         n: usize,
+//!         a: [i64; n],
+//!     }
+//!     println!("{}", a.iter().sum::<i64>());
 //! }
 //! # }
 //! ```
 //!
-//! *likely* prints like
+//! If the number of test cases is not given and the input just continues until EOF, use
+//! `#[proconio::main(until_eof)]` instead.  Without arguments, `#[proconio::main]` is the same as
+//! `#[fastout]`.
+//!
+//! # `output!` macro
+//!
+//! `output!` is the output counterpart of `input!`.  It takes values with the same kinds as
+//! `input!`, and writes them one per line into [`proconio::out`](out).
 //!
-//! ```text
-//! between
-//! hello
-//! world
 //! ```
+//! # extern crate proconio;
+//! use proconio::marker::{Chars, Fixed, Usize1};
+//! use proconio::output;
+//!
+//! let n = 3;
+//! let a = vec![1, 2, 3];
+//! let parent = vec![0, 0, 1];
+//! let grid = vec![vec!['#', '.'], vec!['.', '#']];
+//! let ok = true;
+//!
+//! output! {
+//!     n,                      // 3
+//!     a: [_; n] sep ',',      // 1,2,3
+//!     parent: [Usize1; n],    // 1 1 2
+//!     grid: [Chars; 2],       // #.
+//!                             // .#
+//!     ok,                     // Yes
+//!     1.0 / 3.0: Fixed<4>,    // 0.3333
+//! }
+//! ```
+//!
+//! Each kind is written by its [`Writable`](output::Writable) implementation: `Usize1` adds 1
+//! back, `Chars` is written without spaces, `bool` is written as `Yes` or `No`, and `Fixed<P>`
+//! writes a floating point number with `P` digits after the decimal point.  You can write to your
+//! own writer by `to writer`, like `from source` of `input!`.
 //!
-//! If you don't like this behavior, you can remove `#[fastout]` from your `main()`.
+//! With `derive` feature, `#[derive_writable]` makes your own struct `Writable`, just like
+//! `#[derive_readable]` makes it `Readable`.  Applying both lets a generator build a value and write
+//! it back as a valid input.
 //!
 
 #[cfg(feature = "derive")]
 pub use proconio_derive::*;
 
+pub mod graph;
+pub mod grid;
 pub mod marker;
+pub mod out;
+pub mod output;
 pub mod source;
 
 use crate::source::{line::LineSource, once::OnceSource};
+use std::collections::{btree_map, hash_map, BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
 use std::sync::OnceLock;
 use std::{
     io::{self, BufRead},
@@ -564,6 +695,9 @@ use std::{
 #[doc(hidden)]
 pub use crate::source::Readable as __Readable;
 
+#[doc(hidden)]
+pub use crate::source::{Kind as __Kind, ViaFromStr as __ViaFromStr, ViaReadable as __ViaReadable};
+
 pub enum StdinSource<R: BufRead> {
     Line(LineSource<R>), // for input!, read_value! and is_stdin_empty()
     Once(OnceSource<R>), // for input_once! and read_value_once!
@@ -661,6 +795,33 @@ macro_rules! input {
     (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [] @rest ($($tt:tt)*) $($rest:tt)*) => {
         $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [($($tt)*)] @rest $($rest)*);
     };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [] @rest {$($tt:tt)*} $($rest:tt)*) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [{$($tt)*}] @rest $($rest)*);
+    };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [$($kind:tt)+] @rest unzip $($rest:tt)*) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$($kind)* unzip] @rest $($rest)*);
+    };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [$($kind:tt)+] @rest transpose $($rest:tt)*) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$($kind)* transpose] @rest $($rest)*);
+    };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [$($kind:tt)+] @rest keep_first $($rest:tt)*) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$($kind)* keep_first] @rest $($rest)*);
+    };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [$($kind:tt)+] @rest keep_last $($rest:tt)*) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$($kind)* keep_last] @rest $($rest)*);
+    };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [$($kind:tt)+] @rest as $($coll:ident)::+, $($rest:tt)*) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$($kind)* as $($coll)::+] @rest, $($rest)*);
+    };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [$($kind:tt)+] @rest as $($coll:ident)::+) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$($kind)* as $($coll)::+] @rest);
+    };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [$($kind:tt)+] @rest as $coll:ty, $($rest:tt)*) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$($kind)* as $coll] @rest, $($rest)*);
+    };
+    (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [$($kind:tt)+] @rest as $coll:ty) => {
+        $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$($kind)* as $coll] @rest);
+    };
     (@from [$source:expr] @mut [$($mut:tt)?] @var $var:tt @kind [] @rest $ty:ty, $($rest:tt)*) => {
         $crate::input!(@from [$source] @mut [$($mut)*] @var $var @kind [$ty] @rest, $($rest)*);
     };
@@ -776,6 +937,82 @@ macro_rules! input_once {
 /// You can use any types that can be used with input! macro.
 #[macro_export]
 macro_rules! read_value {
+    // array of tuples unzipped into a tuple of arrays
+    (@source [$source:expr] @kind [[$($kind:tt)*] unzip]) => {
+        $crate::__Unzip::__unzip($crate::read_value!(@source [$source] @kind [[$($kind)*]]))
+    };
+
+    // matrix given column by column, transposed into rows
+    (@source [$source:expr] @kind [[$($kind:tt)*] transpose]) => {
+        $crate::__transpose($crate::read_value!(@source [$source] @kind [[$($kind)*]]))
+    };
+
+    // matrix read into a contiguous grid
+    (@source [$source:expr] @kind [[[$($row:tt)*]; $($h:tt)*] as Matrix]) => {
+        $crate::read_value!(@matrix @source [$source] @height [$($h)*] @elem [] @rest $($row)*)
+    };
+    (@source [$source:expr] @kind [[[$($row:tt)*]] as Matrix]) => {{
+        let height = <usize as $crate::__Readable>::read($source);
+        $crate::read_value!(@matrix @source [$source] @height [height] @elem [] @rest $($row)*)
+    }};
+    (@matrix @source [$source:expr] @height [$($h:tt)*] @elem [$($elem:tt)*] @rest ; $($w:tt)*) => {{
+        let height = $($h)*;
+        let width = $($w)*;
+        $crate::grid::Grid::from_fn(height, width, |_, _| {
+            $crate::read_value!(@source [$source] @kind [$($elem)*])
+        })
+    }};
+    (@matrix @source [$source:expr] @height [$($h:tt)*] @elem [$($elem:tt)*] @rest $tt:tt $($rest:tt)*) => {
+        $crate::read_value!(@matrix @source [$source] @height [$($h)*] @elem [$($elem)* $tt] @rest $($rest)*)
+    };
+    (@matrix @source [$source:expr] @height [$($h:tt)*] @elem [$($elem:tt)*] @rest) => {
+        compile_error!("`as Matrix` requires the width of the rows like `[[T; w]; h] as Matrix`.")
+    };
+
+    // array collected into a collection: `as BTreeSet` or `as BTreeSet<i64>`
+    (@source [$source:expr] @kind [[$($kind:tt)*] as $($coll:ident)::+]) => {{
+        #[allow(unused_imports)]
+        use ::std::iter::FromIterator as _;
+        $($coll)::+::from_iter($crate::read_value!(@source [$source] @kind [[$($kind)*]]))
+    }};
+    (@source [$source:expr] @kind [[$($kind:tt)*] as $coll:ty]) => {
+        <$coll as ::std::iter::FromIterator<_>>::from_iter(
+            $crate::read_value!(@source [$source] @kind [[$($kind)*]]),
+        )
+    };
+
+    // map from key-value pairs, panicking on a duplicate key unless `keep_first` or `keep_last`
+    (@source [$source:expr] @kind [{$($kind:tt)*} keep_first $($rest:tt)*]) => {
+        $crate::read_value!(@map @source [$source] @on_duplicate [KeepFirst] @kind [{$($kind)*} $($rest)*])
+    };
+    (@source [$source:expr] @kind [{$($kind:tt)*} keep_last $($rest:tt)*]) => {
+        $crate::read_value!(@map @source [$source] @on_duplicate [KeepLast] @kind [{$($kind)*} $($rest)*])
+    };
+    (@source [$source:expr] @kind [{$($kind:tt)*} $($rest:tt)*]) => {
+        $crate::read_value!(@map @source [$source] @on_duplicate [Panic] @kind [{$($kind)*} $($rest)*])
+    };
+    (@map @source [$source:expr] @on_duplicate [$on_duplicate:ident] @kind [{$($kind:tt)*} as $($coll:ident)::+]) => {
+        $crate::__collect_map(
+            $($coll)::+::new(),
+            $crate::read_value!(@source [$source] @kind [[$($kind)*]]),
+            $crate::__OnDuplicate::$on_duplicate,
+        )
+    };
+    (@map @source [$source:expr] @on_duplicate [$on_duplicate:ident] @kind [{$($kind:tt)*} as $coll:ty]) => {
+        $crate::__collect_map(
+            <$coll>::new(),
+            $crate::read_value!(@source [$source] @kind [[$($kind)*]]),
+            $crate::__OnDuplicate::$on_duplicate,
+        )
+    };
+    (@map @source [$source:expr] @on_duplicate [$on_duplicate:ident] @kind [{$($kind:tt)*}]) => {
+        $crate::__collect_map(
+            ::std::collections::HashMap::new(),
+            $crate::read_value!(@source [$source] @kind [[$($kind)*]]),
+            $crate::__OnDuplicate::$on_duplicate,
+        )
+    };
+
     // array and variable length array
     (@source [$source:expr] @kind [[$($kind:tt)*]]) => {
         $crate::read_value!(@array @source [$source] @kind [] @rest $($kind)*)
@@ -816,10 +1053,12 @@ macro_rules! read_value {
         $crate::read_value!(@tuple @source [$source] @kinds [$($kinds)*] @current [$($curr)* $tt] @rest $($rest)*)
     };
 
-    // normal other
-    (@source [$source:expr] @kind [$kind:ty]) => {
-        <$kind as $crate::__Readable>::read($source)
-    };
+    // normal other, falling back to `FromStr` if the kind is not `Readable`
+    (@source [$source:expr] @kind [$kind:ty]) => {{
+        #[allow(unused_imports)]
+        use $crate::{__ViaFromStr as _, __ViaReadable as _};
+        (&&$crate::__Kind::<$kind>(::std::marker::PhantomData)).__proconio_read($source)
+    }};
 
     // runtime readable
     (@source [$source:expr] @dyn_kind [$dyn_kind:expr]) => {
@@ -932,6 +1171,143 @@ pub fn __acquire_global_stdin_lock_once() -> MutexGuard<'static, StdinSource<Buf
     locked_stdin
 }
 
+// Collects the key-value pairs read by `{(K, V); n}` into the map, resolving a duplicate key by
+// `on_duplicate`.  This must be public because it appears in macro-expanded code, but hidden in doc
+// because this implementation detail should be considered as private.
+#[doc(hidden)]
+pub fn __collect_map<M, K, V>(mut map: M, entries: Vec<(K, V)>, on_duplicate: __OnDuplicate) -> M
+where
+    M: __Map<K, V>,
+    K: Debug,
+{
+    for (key, value) in entries {
+        if let Err(key) = map.__insert(key, value, on_duplicate) {
+            panic!(
+                concat!(
+                    "duplicate key `{:?}` in the map; ",
+                    "add `keep_first` or `keep_last` after the braces to allow the same key."
+                ),
+                key
+            );
+        }
+    }
+    map
+}
+
+// What to do on a duplicate key in `{(K, V); n}`, selected by the suffix after the braces.
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub enum __OnDuplicate {
+    Panic,
+    KeepFirst,
+    KeepLast,
+}
+
+// The maps which `{(K, V); n}` can be collected into.
+#[doc(hidden)]
+pub trait __Map<K, V> {
+    // Inserts the pair, returning the key back if it is duplicate and `on_duplicate` is `Panic`.
+    fn __insert(&mut self, key: K, value: V, on_duplicate: __OnDuplicate) -> Result<(), K>;
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> __Map<K, V> for HashMap<K, V, S> {
+    fn __insert(&mut self, key: K, value: V, on_duplicate: __OnDuplicate) -> Result<(), K> {
+        match self.entry(key) {
+            hash_map::Entry::Occupied(entry) => match on_duplicate {
+                __OnDuplicate::Panic => Err(entry.remove_entry().0),
+                __OnDuplicate::KeepFirst => Ok(()),
+                __OnDuplicate::KeepLast => {
+                    *entry.into_mut() = value;
+                    Ok(())
+                }
+            },
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(value);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<K: Ord, V> __Map<K, V> for BTreeMap<K, V> {
+    fn __insert(&mut self, key: K, value: V, on_duplicate: __OnDuplicate) -> Result<(), K> {
+        match self.entry(key) {
+            btree_map::Entry::Occupied(entry) => match on_duplicate {
+                __OnDuplicate::Panic => Err(entry.remove_entry().0),
+                __OnDuplicate::KeepFirst => Ok(()),
+                __OnDuplicate::KeepLast => {
+                    *entry.into_mut() = value;
+                    Ok(())
+                }
+            },
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(value);
+                Ok(())
+            }
+        }
+    }
+}
+
+// Unzips an array of tuples read by `[(A, B); n] unzip` into a tuple of arrays.  This must be
+// public because it appears in macro-expanded code, but hidden in doc because this implementation
+// detail should be considered as private.
+#[doc(hidden)]
+pub trait __Unzip {
+    type Output;
+    fn __unzip(self) -> Self::Output;
+}
+
+macro_rules! impl_unzip_for_tuples {
+    ($($t:ident $idx:tt),+) => {
+        impl<$($t),+> __Unzip for Vec<($($t,)+)> {
+            type Output = ($(Vec<$t>,)+);
+            fn __unzip(self) -> Self::Output {
+                let mut res = ($(Vec::<$t>::with_capacity(self.len()),)+);
+                for value in self {
+                    $(res.$idx.push(value.$idx);)+
+                }
+                res
+            }
+        }
+    };
+}
+
+impl_unzip_for_tuples!(T1 0);
+impl_unzip_for_tuples!(T1 0, T2 1);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2, T4 3);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2, T4 3, T5 4);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9);
+impl_unzip_for_tuples!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9, T11 10);
+impl_unzip_for_tuples!(
+    T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9, T11 10, T12 11
+);
+
+// Transposes the matrix read by `[[T; h]; w] transpose`.  This must be public because it appears
+// in macro-expanded code, but hidden in doc because this implementation detail should be
+// considered as private.
+#[doc(hidden)]
+pub fn __transpose<T>(matrix: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    let width = matrix.first().map_or(0, Vec::len);
+    let mut res: Vec<Vec<T>> = (0..width)
+        .map(|_| Vec::with_capacity(matrix.len()))
+        .collect();
+    for row in matrix {
+        assert!(
+            row.len() == width,
+            "cannot transpose the matrix whose rows have different lengths"
+        );
+        for (column, value) in res.iter_mut().zip(row) {
+            column.push(value);
+        }
+    }
+    res
+}
+
 fn lock_global_stdin_source(
     init: impl FnOnce() -> StdinSource<BufReader<Stdin>>,
 ) -> MutexGuard<'static, StdinSource<BufReader<Stdin>>> {
@@ -1171,7 +1547,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "attempted to read the value 0 as a Usize1")]
     fn input_zero_as_usize1() {
         use crate::marker::Usize1;
         let mut source = AutoSource::from("0");
@@ -1182,7 +1558,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic(expected = "as a Isize1: the value is isize::MIN and cannot be decremented")]
     fn input_min_as_isize1() {
         use crate::marker::Isize1;
         let min_string = isize::MIN.to_string();
@@ -1193,6 +1569,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn input_offset() {
+        use crate::marker::{Offset, Usize0, UsizeMinus, I128_1, I8_1, U128_1, U8_1};
+
+        let max = u128::MAX.to_string();
+        let input = format!("1 255 -127 -1 0 0 {} -5 250", max);
+        let source = AutoSource::from(&*input);
+        input! {
+            from source,
+            a: (U8_1, U8_1),
+            b: (I8_1, I128_1),
+            c: (Usize0, UsizeMinus),
+            d: U128_1,
+            e: Offset<i16, -10>,
+            f: Offset<u8, 100>,
+        }
+
+        assert_eq!(a, (0, 254));
+        assert_eq!(b, (-128, -2));
+        assert_eq!(c, (0, 1));
+        assert_eq!(d, u128::MAX - 1);
+        assert_eq!(e, 5);
+        assert_eq!(f, 150);
+
+        let out = output_to_string(|out| {
+            output! {
+                to out,
+                a: (U8_1, U8_1),
+                b: (I8_1, I128_1),
+                c: (Usize0, UsizeMinus),
+                d: U128_1,
+                e: Offset<i16, -10>,
+            }
+        });
+        assert_eq!(out, format!("1 255\n-127 -1\n0 0\n{}\n-5\n", max));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "attempted to read the value -128 as a I8_1: the value is i8::MIN and cannot be decremented"
+    )]
+    fn input_min_as_i8_1() {
+        use crate::marker::I8_1;
+        let mut source = AutoSource::from("-128");
+        input! {
+            from &mut source,
+            _v: I8_1,
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "attempted to read the value 50 as a `Offset<u8, 100>`: the result overflows"
+    )]
+    fn input_overflow_offset() {
+        use crate::marker::Offset;
+        let mut source = AutoSource::from("50");
+        input! {
+            from &mut source,
+            _v: Offset<u8, 100>,
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to write u8::MAX as a U8_1")]
+    fn output_max_as_u8_1() {
+        use crate::marker::U8_1;
+        output! {
+            to Vec::new(),
+            255: U8_1,
+        }
+    }
+
     #[test]
     fn input_mut() {
         let mut source = AutoSource::from("8 1 2 3 4 5 6 7 8");
@@ -1304,42 +1753,526 @@ mod tests {
     }
 
     #[test]
-    fn input_runtime_readable() {
-        // VecReadable<T> replicates the built-in Vec reader `input!(v: [T])` in user-land.
-        struct VecReadable<T> {
-            n: usize,
-            _marker: PhantomData<T>,
-        }
+    fn readable_tuple_and_array() {
+        use crate::marker::{Chars, Usize1};
+        use crate::source::Readable;
 
-        impl<T> VecReadable<T> {
-            pub fn new(n: usize) -> Self {
-                VecReadable {
-                    n,
-                    _marker: PhantomData,
-                }
-            }
-        }
+        let mut source = AutoSource::from("1 2 abc 4 5 6 1 2 3 4 5 6 7 8 9 10 11 12 13");
 
-        impl<T: crate::source::Readable> crate::source::RuntimeReadable for VecReadable<T> {
-            type Output = Vec<T::Output>;
+        let tuple = <(Usize1, i32, Chars)>::read(&mut source);
+        let array = <[(Usize1, u8); 1]>::read(&mut source);
+        let nested = <[Usize1; 2]>::read(&mut source);
+        let long = <(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8)>::read(&mut source);
 
-            fn read<R: std::io::BufRead, S: crate::source::Source<R>>(
-                self,
-                source: &mut S,
-            ) -> Self::Output {
-                let mut res = vec![];
-                for _ in 0..self.n {
-                    input! {
-                        from &mut *source,
-                        v: T,
-                    }
-                    res.push(v);
-                }
-                res
+        assert_eq!(tuple, (0, 2, vec!['a', 'b', 'c']));
+        assert_eq!(array, [(3, 5)]);
+        assert_eq!(nested, [5, 0]);
+        assert_eq!(long, (2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13));
+    }
+
+    #[test]
+    fn input_from_str_types() {
+        use crate::source::Source;
+        use std::io::BufRead;
+        use std::net::Ipv4Addr;
+        use std::str::FromStr;
+
+        // implements only `FromStr`, like the big integers of other crates.
+        #[derive(PartialEq, Debug)]
+        struct Hex(u32);
+
+        impl FromStr for Hex {
+            type Err = std::num::ParseIntError;
+            fn from_str(s: &str) -> Result<Hex, Self::Err> {
+                u32::from_str_radix(s, 16).map(Hex)
             }
         }
 
-        let mut source = AutoSource::from("4 1 2 3 4");
+        fn read_generic<T: FromStr, R: BufRead, S: Source<R>>(source: &mut S) -> T
+        where
+            T::Err: std::fmt::Debug,
+        {
+            read_value!(from source, T)
+        }
+
+        let mut source = AutoSource::from("10.0.0.1 ff 1a 2b 7f 3 127.0.0.1 9");
+        input! {
+            from &mut source,
+            addr: Ipv4Addr,
+            hex: Hex,
+            hexes: [Hex; 2],
+            pair: (Hex, usize),
+        }
+
+        assert_eq!(addr, Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(hex, Hex(255));
+        assert_eq!(hexes, [Hex(0x1a), Hex(0x2b)]);
+        assert_eq!(pair, (Hex(0x7f), 3));
+        assert_eq!(
+            read_generic::<Ipv4Addr, _, _>(&mut source),
+            Ipv4Addr::LOCALHOST
+        );
+        assert_eq!(read_generic::<Hex, _, _>(&mut source), Hex(9));
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to parse the input `xyz` to the value of type")]
+    fn input_from_str_invalid() {
+        let source = AutoSource::from("xyz");
+        input! {
+            from source,
+            _addr: std::net::Ipv4Addr,
+        }
+    }
+
+    #[test]
+    fn input_std_wrappers() {
+        use crate::marker::{Optional, Usize1};
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+        use std::num::{NonZeroI32, NonZeroUsize, Saturating, Wrapping};
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        let source = AutoSource::from("3 1 4 1 5 255 7 8 9 -1 2 -1 3 -5 -1 1");
+        input! {
+            from source,
+            heap: [Reverse<i64>; 3],
+            (a, b): (Wrapping<u8>, Saturating<u8>),
+            non_zero: (NonZeroUsize, NonZeroI32),
+            boxed: (Box<i32>, Rc<Usize1>, Arc<i32>),
+            parent: [Optional<Usize1>; 3],
+            zero: Optional<i32, 0>,
+            negative: Optional<i32>,
+            single: Optional<(Usize1,)>,
+        }
+
+        let mut heap = heap.into_iter().collect::<BinaryHeap<_>>();
+        assert_eq!(heap.pop(), Some(Reverse(1)));
+        assert_eq!(heap.pop(), Some(Reverse(3)));
+        assert_eq!(a + Wrapping(255), Wrapping(0));
+        assert_eq!(b, Saturating(5));
+        assert_eq!(
+            non_zero,
+            (NonZeroUsize::new(255).unwrap(), NonZeroI32::new(7).unwrap())
+        );
+        assert_eq!(boxed, (Box::new(8), Rc::new(8), Arc::new(-1)));
+        assert_eq!(parent, [Some(1), None, Some(2)]);
+        assert_eq!(zero, Some(-5));
+        assert_eq!(negative, None);
+        assert_eq!(single, Some((0,)));
+    }
+
+    #[test]
+    fn input_optional_of_multiple_tokens() {
+        use crate::marker::{Chars, Optional, Usize1};
+
+        let source = AutoSource::from("-1 3 -4 -1 2 ab 5 1 0 9");
+        input! {
+            from source,
+            edges: [Optional<(Usize1, i64)>; 2],
+            named: (Optional<(Chars, usize)>, usize),
+            word: Optional<(Chars, usize)>,
+            nested: [Optional<Optional<u8, 0>, 1>; 3],
+        }
+
+        assert_eq!(edges, [None, Some((2, -4))]);
+        assert_eq!(named, (None, 2));
+        assert_eq!(word, Some((vec!['a', 'b'], 5)));
+        assert_eq!(nested, [None, Some(None), Some(Some(9))]);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempted to read the value 0 as a NonZeroU32")]
+    fn input_zero_as_non_zero() {
+        let source = AutoSource::from("0");
+        input! {
+            from source,
+            _n: std::num::NonZeroU32,
+        }
+    }
+
+    #[test]
+    fn input_collections() {
+        use crate::marker::Usize1;
+        use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+
+        let source = AutoSource::from("3 3 1 3 2 1 2 3 4 5 2 3 2 a 1 b 2 c 1 a 2 b 3 x 1 x 2 4 5");
+        input! {
+            from source,
+            n: usize,
+            set: [i64; n] as BTreeSet,
+            hash_set: [Usize1] as HashSet<usize>,
+            deque: [u8; 3] as VecDeque,
+            heap: [i32; 2] as BinaryHeap<_>,
+            map: {(char, i32)},
+            sorted: {(String, i64); 3} as BTreeMap,
+            last: [(char, i32); 2] as HashMap<_, _>,
+            nested: [[i32; 2] as BTreeSet; 1],
+        }
+
+        assert_eq!(set, BTreeSet::from([1, 3]));
+        assert_eq!(hash_set, HashSet::from([0, 1]));
+        assert_eq!(deque, [3, 4, 5]);
+        assert_eq!(heap.into_sorted_vec(), [2, 3]);
+        assert_eq!(map, HashMap::from([('a', 1), ('b', 2)]));
+        assert_eq!(
+            sorted,
+            BTreeMap::from([
+                ("a".to_string(), 2),
+                ("b".to_string(), 3),
+                ("c".to_string(), 1)
+            ])
+        );
+        assert_eq!(last, HashMap::from([('x', 2)]));
+        assert_eq!(nested, [BTreeSet::from([4, 5])]);
+    }
+
+    #[test]
+    fn read_value_collections() {
+        use crate::marker::{Collect, LenPrefixed};
+        use std::collections::{BTreeMap, BTreeSet};
+
+        let mut source = AutoSource::from("3 1 2 1 x 5 2 3 4");
+        let set = read_value!(from &mut source, [u32; 3] as std::collections::BTreeSet);
+        let map = read_value!(from &mut source, {(char, i32)} as BTreeMap<char, i32>);
+        let collected = read_value!(from &mut source, Collect<BTreeSet<i32>, LenPrefixed<i32>>);
+
+        assert_eq!(set, BTreeSet::from([1, 2, 3]));
+        assert_eq!(map, BTreeMap::from([('x', 5)]));
+        assert_eq!(collected, BTreeSet::from([3, 4]));
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate key `'a'` in the map")]
+    fn input_duplicate_key() {
+        let source = AutoSource::from("2 a 1 a 2");
+        input! {
+            from source,
+            _map: {(char, i32)},
+        }
+    }
+
+    #[test]
+    fn input_duplicate_key_keep_first() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let source = AutoSource::from("3 a 1 b 2 a 3 a 4 a 5");
+        input! {
+            from source,
+            map: {(char, i32)} keep_first,
+            sorted: {(char, i32); 2} keep_first as BTreeMap<char, i32>,
+        }
+
+        assert_eq!(map, HashMap::from([('a', 1), ('b', 2)]));
+        assert_eq!(sorted, BTreeMap::from([('a', 4)]));
+    }
+
+    #[test]
+    fn input_duplicate_key_keep_last() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let mut source = AutoSource::from("3 a 1 b 2 a 3 a 4 a 5");
+        input! {
+            from &mut source,
+            map: {(char, i32)} keep_last,
+        }
+        let sorted = read_value!(from &mut source, {(char, i32); 2} keep_last as BTreeMap);
+
+        assert_eq!(map, HashMap::from([('a', 3), ('b', 2)]));
+        assert_eq!(sorted, BTreeMap::from([('a', 5)]));
+    }
+
+    #[test]
+    fn input_unzip_and_transpose() {
+        use crate::marker::{Chars, Usize1};
+
+        let source = AutoSource::from("3 1 10 2 20 3 30 2 1 x ab 2 y cd 1 2 3 4 5 6 2 7 8 9");
+        input! {
+            from source,
+            n: usize,
+            (a, b): [(Usize1, i64); n] unzip,
+            (c, d, e): [(u8, char, Chars)] unzip,
+            matrix: [[i32; 2]; 3] transpose,
+            (single,): [(i32,); 1] unzip,
+            pair: ([[u8; 1]; 2] transpose, i32),
+        }
+
+        assert_eq!(a, [0, 1, 2]);
+        assert_eq!(b, [10, 20, 30]);
+        assert_eq!(c, [1, 2]);
+        assert_eq!(d, ['x', 'y']);
+        assert_eq!(e, [vec!['a', 'b'], vec!['c', 'd']]);
+        assert_eq!(matrix, [vec![1, 3, 5], vec![2, 4, 6]]);
+        assert_eq!(single, [2]);
+        assert_eq!(pair, (vec![vec![7, 8]], 9));
+    }
+
+    #[test]
+    fn read_value_transpose_empty() {
+        let mut source = AutoSource::from("0");
+        let matrix = read_value!(from &mut source, [[i32; 3]] transpose);
+        assert!(matrix.is_empty());
+    }
+
+    #[test]
+    fn input_matrix() {
+        use crate::grid::Grid;
+        use crate::marker::{Matrix, Usize1};
+
+        let source = AutoSource::from("2 3 1 2 3 4 5 6 2 1 2 2 1 1 2 3");
+        input! {
+            from source,
+            h: usize,
+            w: usize,
+            mut a: [[i64; w]; h] as Matrix,
+            b: [[(Usize1, u8); 1]] as Matrix,
+            c: with Matrix::<Usize1>::new(1, 3),
+        }
+
+        assert_eq!((a.height(), a.width()), (2, 3));
+        assert_eq!(a[(1, 0)], 4);
+        assert_eq!(a.row(1), [4, 5, 6]);
+        assert_eq!(a.get(1, 2), Some(&6));
+        assert_eq!(a.get(2, 0), None);
+        assert_eq!(a.get(0, -1), None);
+        assert_eq!(a.offset((1, 1), (-1, 1)), Some((0, 2)));
+        assert_eq!(a.offset((0, 0), (-1, 0)), None);
+        a[(0, 0)] = 10;
+        a.row_mut(1)[2] = 60;
+        assert_eq!(a.as_slice(), [10, 2, 3, 4, 5, 60]);
+        assert_eq!(
+            a.neighbors4(0, 1).collect::<Vec<_>>(),
+            [(1, 1), (0, 2), (0, 0)]
+        );
+        assert_eq!(a.neighbors8(1, 2).count(), 3);
+        assert_eq!(b, Grid::from_vec(2, 1, vec![(0, 2), (1, 1)]));
+        assert_eq!(c.into_vec(), [0, 1, 2]);
+
+        let out = output_to_string(|out| {
+            output! {
+                to out,
+                a: Matrix<i64>,
+            }
+        });
+        assert_eq!(out, "10 2 3\n4 5 60\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "the position (2, 0) is out of the grid 2x2")]
+    fn grid_index_out_of_range() {
+        let grid = crate::grid::Grid::from_fn(2, 2, |i, j| i * 2 + j);
+        let _ = grid[(2, 0)];
+    }
+
+    #[test]
+    fn input_char_grids() {
+        use crate::marker::{BoolGrid, ByteGrid, CharGrid};
+
+        let source = AutoSource::from("S.#\n..G\n# . #\n. . .\nab\n.#\n#.\n");
+        input! {
+            from source,
+            maze: with CharGrid::new(2),
+            split: with CharGrid::with_width(2, 3),
+            bytes: with ByteGrid::new(1),
+            wall: with BoolGrid::with_width(2, 2, '#'),
+        }
+
+        assert_eq!((maze.height(), maze.width()), (2, 3));
+        assert_eq!(maze.find(&'S'), Some((0, 0)));
+        assert_eq!(maze.find(&'G'), Some((1, 2)));
+        assert_eq!(maze.find(&'X'), None);
+        assert_eq!(maze.find_all(&'.').count(), 3);
+        assert_eq!(split.as_slice(), ['#', '.', '#', '.', '.', '.']);
+        assert_eq!(bytes.row(0), b"ab");
+        assert_eq!(wall.as_slice(), [false, true, true, false]);
+        assert_eq!(
+            maze.map(|&c| c == '#').as_slice(),
+            [false, false, true, false, false, false]
+        );
+
+        let out = output_to_string(|out| {
+            output! {
+                to out,
+                maze: CharGrid,
+                bytes: ByteGrid,
+            }
+        });
+        assert_eq!(out, "S.#\n..G\nab\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "the row 2 `#..` has 3 cells, but the width of the grid is 2")]
+    fn input_char_grid_different_width() {
+        use crate::marker::CharGrid;
+
+        let source = AutoSource::from("..\n#..\n");
+        input! {
+            from source,
+            _grid: with CharGrid::new(2),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the row 2 `#` has 1 cells, but the width of the grid is 3")]
+    fn input_char_grid_short_row() {
+        use crate::marker::CharGrid;
+
+        // the short row must not swallow the tokens of the next rows.
+        let source = AutoSource::from("#.#\n#\n. .\n");
+        input! {
+            from source,
+            _grid: with CharGrid::with_width(3, 3),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the row 2 `#. . #` has 4 cells, but the width of the grid is 3")]
+    fn input_char_grid_split_long_cell() {
+        use crate::marker::CharGrid;
+
+        let source = AutoSource::from("# . #\n#. . #\n");
+        input! {
+            from source,
+            _grid: with CharGrid::with_width(2, 3),
+        }
+    }
+
+    #[test]
+    fn input_graphs() {
+        use crate::graph::{Csr, Directed, ParentArray, TreeEdges, Undirected};
+
+        let source = AutoSource::from(concat!(
+            "3 3\n1 2\n2 3\n3 1\n",
+            "3 2\n0 1 10\n2 1 -5\n",
+            "4\n1 2\n1 3\n3 4\n",
+            "4\n1 1 2\n",
+            "3\n1 4\n2 6\n",
+            "3 1\n2 3\n",
+            "3 2\n0 1 10\n2 1 -5\n",
+        ));
+        input! {
+            from source,
+            n: usize,
+            m: usize,
+            directed: with Directed::new(n, m),
+            n: usize,
+            m: usize,
+            undirected: with Undirected::new(n, m).zero_indexed().weighted::<i64>(),
+            n: usize,
+            tree: with TreeEdges::new(n).csr(),
+            n: usize,
+            children: with ParentArray::new(n),
+            n: usize,
+            weighted_children: with ParentArray::new(n).weighted::<u32>().csr(),
+            n: usize,
+            m: usize,
+            single: with Directed::new(n, m).csr(),
+            n: usize,
+            m: usize,
+            // the options can be given in any order.
+            csr_weighted: with Undirected::new(n, m).csr().weighted::<i64>().zero_indexed(),
+        }
+
+        assert_eq!(directed, [vec![1], vec![2], vec![0]]);
+        assert_eq!(
+            undirected,
+            [vec![(1, 10)], vec![(0, 10), (2, -5)], vec![(1, -5)]]
+        );
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.num_arcs(), 6);
+        assert_eq!(
+            tree.iter().collect::<Vec<_>>(),
+            [&[1, 2][..], &[0], &[0, 3], &[2]]
+        );
+        assert_eq!(children, [vec![1, 2], vec![3], vec![], vec![]]);
+        assert_eq!(weighted_children[0], [(1, 4)]);
+        assert_eq!(weighted_children[1], [(2, 6)]);
+        assert!(weighted_children[2].is_empty());
+        assert_eq!(single, Csr::new(3, vec![(1, 2)]));
+        assert_eq!(
+            csr_weighted.iter().collect::<Vec<_>>(),
+            undirected.iter().map(|adj| &adj[..]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the vertex 4 of the edge 2 is out of range 1..4")]
+    fn input_graph_vertex_out_of_range() {
+        use crate::graph::Undirected;
+
+        let source = AutoSource::from("3 2\n1 2\n3 4\n");
+        input! {
+            from source,
+            n: usize,
+            m: usize,
+            _g: with Undirected::new(n, m),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the parent p_3 = 0 is out of range 1..5")]
+    fn input_parent_out_of_range() {
+        use crate::graph::ParentArray;
+
+        let source = AutoSource::from("4\n1 0 2\n");
+        input! {
+            from source,
+            n: usize,
+            _children: with ParentArray::new(n),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the parent p_2 = 3 is out of range 0..3")]
+    fn input_parent_out_of_range_zero_indexed() {
+        use crate::graph::ParentArray;
+
+        let source = AutoSource::from("3\n0 3\n");
+        input! {
+            from source,
+            n: usize,
+            _children: with ParentArray::new(n).zero_indexed(),
+        }
+    }
+
+    #[test]
+    fn input_runtime_readable() {
+        // VecReadable<T> replicates the built-in Vec reader `input!(v: [T])` in user-land.
+        struct VecReadable<T> {
+            n: usize,
+            _marker: PhantomData<T>,
+        }
+
+        impl<T> VecReadable<T> {
+            pub fn new(n: usize) -> Self {
+                VecReadable {
+                    n,
+                    _marker: PhantomData,
+                }
+            }
+        }
+
+        impl<T: crate::source::Readable> crate::source::RuntimeReadable for VecReadable<T> {
+            type Output = Vec<T::Output>;
+
+            fn read<R: std::io::BufRead, S: crate::source::Source<R>>(
+                self,
+                source: &mut S,
+            ) -> Self::Output {
+                let mut res = vec![];
+                for _ in 0..self.n {
+                    input! {
+                        from &mut *source,
+                        v: T,
+                    }
+                    res.push(v);
+                }
+                res
+            }
+        }
+
+        let mut source = AutoSource::from("4 1 2 3 4");
         input! {
             from &mut source,
             n: usize,
@@ -1411,4 +2344,332 @@ mod tests {
             _n: i32,
         }
     }
+
+    #[test]
+    fn from_token_integers() {
+        use crate::source::FromToken;
+
+        fn check<T: FromToken + std::str::FromStr + PartialEq + std::fmt::Debug>(token: &str)
+        where
+            T::Err: std::fmt::Debug,
+        {
+            assert_eq!(
+                T::from_token(token),
+                token.parse::<T>().unwrap(),
+                "{}",
+                token
+            );
+        }
+
+        for token in ["0", "7", "+7", "-7", "-0", "127", "-128"] {
+            check::<i8>(token);
+        }
+        for token in ["0", "+255", "255"] {
+            check::<u8>(token);
+        }
+        for token in ["9223372036854775807", "-9223372036854775808", "-00123"] {
+            check::<i64>(token);
+        }
+        check::<u64>("18446744073709551615");
+        check::<i128>("-170141183460469231731687303715884105728");
+        check::<usize>("000");
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to parse the input `128` to the value of type `i8`")]
+    fn from_token_overflow() {
+        use crate::source::FromToken;
+        i8::from_token("128");
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to parse the input `-1` to the value of type `u32`")]
+    fn from_token_negative_unsigned() {
+        use crate::source::FromToken;
+        u32::from_token("-1");
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to parse the input `1_000` to the value of type `i64`")]
+    fn from_token_invalid_digit() {
+        use crate::source::FromToken;
+        i64::from_token("1_000");
+    }
+
+    #[test]
+    fn readable_from_token_and_parse() {
+        use crate::marker::Parse;
+        use crate::source::{parse_token, FromToken, Readable, Source};
+        use std::cmp::Reverse;
+        use std::io::BufRead;
+        use std::net::Ipv4Addr;
+
+        // a user-defined `FromToken` type is `Readable`.
+        #[derive(PartialEq, Debug)]
+        struct Id(usize);
+
+        impl FromToken for Id {
+            fn from_token(token: &str) -> Id {
+                Id(usize::from_token(token.strip_prefix('#').unwrap()))
+            }
+        }
+
+        // a user-defined `Readable` type reading a `FromStr` type by `parse_token`.
+        #[derive(PartialEq, Debug)]
+        struct Route(Ipv4Addr, Ipv4Addr);
+
+        impl Readable for Route {
+            type Output = Route;
+            fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Route {
+                Route(parse_token(source), parse_token(source))
+            }
+        }
+
+        let source = AutoSource::from("#3 #1 #4 10.0.0.1 10.0.0.2 127.0.0.1 0.0.0.0");
+        input! {
+            from source,
+            id: Id,
+            ids: [Reverse<Id>; 2],
+            route: Route,
+            addrs: (Parse<Ipv4Addr>, Reverse<Parse<Ipv4Addr>>),
+        }
+
+        assert_eq!(id, Id(3));
+        assert_eq!(ids, [Reverse(Id(1)), Reverse(Id(4))]);
+        assert_eq!(
+            route,
+            Route(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2))
+        );
+        assert_eq!(addrs, (Ipv4Addr::LOCALHOST, Reverse(Ipv4Addr::UNSPECIFIED)));
+    }
+
+    #[test]
+    fn fast_write_integers() {
+        use crate::out::FastWrite;
+
+        fn check<T: FastWrite + std::fmt::Display>(value: T) {
+            let mut buf = Vec::new();
+            value.fast_write(&mut buf);
+            assert_eq!(String::from_utf8(buf).unwrap(), value.to_string());
+        }
+
+        for n in [0, 1, 9, 10, 99, 100, 101, 12345, u64::MAX] {
+            check(n);
+        }
+        for n in [0, -1, -10, 99, -100, i64::MIN, i64::MAX] {
+            check(n);
+        }
+        for n in [
+            0,
+            u64::MAX as u128 + 1,
+            10u128.pow(19),
+            10u128.pow(38),
+            u128::MAX,
+        ] {
+            check(n);
+        }
+        check(i128::MIN);
+        check(i8::MIN);
+        check(u8::MAX);
+        check('あ');
+        check("str");
+    }
+
+    #[test]
+    fn fast_write_fixed() {
+        use crate::out::fixed;
+
+        for &value in &[
+            0.0, -0.0, 0.125, 1.5, 2.5, -3.25, 1e-7, 123456.789, 1e20, -1e300,
+        ] {
+            for precision in [0, 1, 2, 3, 10, 20] {
+                assert_eq!(
+                    fixed(value, precision).to_string(),
+                    format!("{:.*}", precision, value),
+                    "value: {}, precision: {}",
+                    value,
+                    precision
+                );
+            }
+        }
+        assert_eq!(fixed(f64::NAN, 3).to_string(), "NaN");
+        assert_eq!(fixed(f64::INFINITY, 3).to_string(), "inf");
+    }
+
+    use crate::output;
+
+    fn output_to_string(f: impl FnOnce(&mut Vec<u8>)) -> String {
+        let mut buf = Vec::new();
+        f(&mut buf);
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn output_kinds() {
+        use crate::marker::{Bytes, Chars, Fixed, Usize1};
+
+        let n = 2;
+        let a = vec![vec![1, 2], vec![3, 4]];
+        let edges = vec![(0, 1), (1, 2)];
+        let s = "abc".chars().collect::<Vec<_>>();
+        let out = output_to_string(|out| {
+            output! {
+                to out,
+                n + 1,
+                a: [[_; n]; n],
+                a: [[_; 2] sep ','; n] sep " | ",
+                a: [[_]],
+                edges: [(Usize1, Usize1)],
+                (n, s): (_, Chars),
+                [b"xy".to_vec(), b"z".to_vec()]: [Bytes; 2] sep ' ',
+                std::f64::consts::PI: Fixed<2>,
+                (1 == 1, 1 == 2),
+                edges,
+                "str",
+            }
+        });
+
+        assert_eq!(
+            out,
+            concat!(
+                "3\n",
+                "1 2\n3 4\n",
+                "1,2 | 3,4\n",
+                "2\n2\n1 2\n2\n3 4\n",
+                "2\n1 2\n2 3\n",
+                "2 abc\n",
+                "xy z\n",
+                "3.14\n",
+                "Yes No\n",
+                "0 1\n1 2\n",
+                "str\n",
+            )
+        );
+    }
+
+    #[test]
+    fn output_largest_tuple() {
+        use crate::marker::Usize1;
+
+        let tuple = (0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11);
+        let rows = [(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12); 2];
+        let out = output_to_string(|out| {
+            output! {
+                to out,
+                tuple: (Usize1, _, _, _, _, _, _, _, _, _, _, Usize1),
+                rows: [(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8); 2],
+            }
+        });
+
+        assert_eq!(
+            out,
+            concat!(
+                "1 1 2 3 4 5 6 7 8 9 10 12\n",
+                "1 2 3 4 5 6 7 8 9 10 11 12\n",
+                "1 2 3 4 5 6 7 8 9 10 11 12\n",
+            )
+        );
+    }
+
+    #[test]
+    fn output_fixed_with_glob_imports() {
+        // `marker` and `out` can be imported together without a name collision.
+        use crate::marker::*;
+        use crate::out::*;
+
+        let out = output_to_string(|out| {
+            output! {
+                to out,
+                2.0 / 3.0: Fixed<3>,
+            }
+        });
+
+        assert_eq!(out, "0.667\n");
+        assert_eq!(fixed(2.0 / 3.0, 3).to_string(), "0.667");
+    }
+
+    #[test]
+    fn output_round_trip() {
+        use crate::marker::{Chars, Usize1};
+
+        let input = "3\n1 3 2\n2\n#.\n.#\n";
+        let source = AutoSource::from(input);
+        input! {
+            from source,
+            n: usize,
+            p: [Usize1; n],
+            grid: [Chars],
+        }
+
+        let out = output_to_string(|out| {
+            output! {
+                to out,
+                n,
+                p: [Usize1; n],
+                grid: [Chars],
+            }
+        });
+        assert_eq!(out, input);
+    }
+
+    #[test]
+    fn len_prefixed_round_trip() {
+        use crate::marker::{Chars, LenPrefixed, Usize1};
+
+        let input = "2\n3\n1 2 3\n0\n\n3\n1 2\n2 3\n2\nab\ncd\n";
+        let source = AutoSource::from(input);
+        input! {
+            from source,
+            rows: LenPrefixed<LenPrefixed<i32>>,
+            edges: LenPrefixed<(Usize1, Usize1), Usize1>,
+            grid: LenPrefixed<Chars>,
+        }
+
+        assert_eq!(rows, [vec![1, 2, 3], vec![]]);
+        assert_eq!(edges, [(0, 1), (1, 2)]);
+        assert_eq!(grid, [vec!['a', 'b'], vec!['c', 'd']]);
+
+        let out = output_to_string(|out| {
+            output! {
+                to out,
+                rows: LenPrefixed<LenPrefixed<i32>>,
+                edges: LenPrefixed<(Usize1, Usize1), Usize1>,
+                grid: LenPrefixed<Chars>,
+            }
+        });
+        assert_eq!(out, input);
+    }
+
+    #[test]
+    fn output_len_prefixed_as_slice() {
+        use crate::marker::LenPrefixed;
+
+        for rows in [vec![], vec![vec![]], vec![vec![1, 2], vec![], vec![3]]] {
+            let slice = output_to_string(|out| {
+                output! {
+                    to out,
+                    Vec::<i32>::new(): [i32],
+                    rows: [[i32]],
+                }
+            });
+            let len_prefixed = output_to_string(|out| {
+                output! {
+                    to out,
+                    Vec::<i32>::new(): LenPrefixed<i32>,
+                    rows: LenPrefixed<LenPrefixed<i32>>,
+                }
+            });
+            assert_eq!(len_prefixed, slice);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the number of elements to write differs from the specified length")]
+    fn output_err_different_length() {
+        let a = vec![1, 2, 3];
+        output! {
+            to Vec::new(),
+            a: [i32; 2],
+        }
+    }
 }
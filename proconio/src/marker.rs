@@ -7,8 +7,17 @@
 
 //! Declares special marker types.
 
-use crate::source::{Readable, Source};
+use crate::grid::Grid;
+use crate::out::FastWrite;
+use crate::output::{write_joined, Writable};
+use crate::source::{parse_token, Readable, RuntimeReadable, Source};
+use std::any::type_name;
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display};
 use std::io::BufRead;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::str::FromStr;
 
 /// Chars: read a string as array of chars.
 pub enum Chars {}
@@ -20,6 +29,16 @@ impl Readable for Chars {
     }
 }
 
+impl Writable for Chars {
+    type Value = Vec<char>;
+    const SEP: &'static str = "\n";
+    fn write(value: &Vec<char>, buf: &mut Vec<u8>) {
+        for c in value {
+            c.fast_write(buf);
+        }
+    }
+}
+
 /// Bytes: read a string as array of bytes.
 pub enum Bytes {}
 
@@ -30,36 +49,697 @@ impl Readable for Bytes {
     }
 }
 
-/// Usize1: 1-indexed usize.  Output of reading has type usize.
-pub enum Usize1 {}
+impl Writable for Bytes {
+    type Value = Vec<u8>;
+    const SEP: &'static str = "\n";
+    fn write(value: &Vec<u8>, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(value);
+    }
+}
+
+/// Offset<T, K>: an integer of type `T` offset by `K`.  Output of reading has type `T`.
+///
+/// Reading subtracts `K` from the value, and writing adds `K` back, so `Offset<usize, 1>`
+/// converts 1-indexed values to 0-indexed ones.  Negative values are not special: they are just
+/// shifted by `K` like `-1` read as `I64_1` is `-2`.  It panics only if the result overflows `T`,
+/// such as `0` read as `Usize1`, or `i64::MIN` read as `I64_1`.  `K` can be negative, so that
+/// `UsizeMinus` adds 1 to the read value.
+///
+/// Aliases are available for the common offsets like `Usize1`, `I64_1` and `Usize0`.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::marker::{Offset, UsizeMinus, I64_1, U32_1};
+/// use proconio::source::auto::AutoSource;
+///
+/// let source = AutoSource::from("1 -1 0 2024");
+/// input! {
+///     from source,
+///     a: U32_1,
+///     b: I64_1,
+///     c: UsizeMinus,
+///     year: Offset<u16, 2000>,
+/// }
+///
+/// assert_eq!((a, b, c, year), (0, -2, 1, 24));
+/// ```
+pub struct Offset<T, const K: i64>(PhantomData<fn() -> T>);
+
+/// An integer type which can be offset by [`Offset`].
+#[doc(hidden)]
+pub trait OffsetInt: Readable<Output = Self> + FastWrite + Copy + Display {
+    /// The name of the alias of `Offset<Self, 1>`, like `Usize1`.
+    const ALIAS: &'static str;
+    const SIGNED: bool;
+
+    fn checked_sub_offset(self, k: i64) -> Option<Self>;
+    fn checked_add_offset(self, k: i64) -> Option<Self>;
+}
+
+// The offset is computed in `i128`, which contains all the integers other than `u128`.
+macro_rules! impl_offset_int {
+    ($($t:ty => $alias:ident),* $(,)?) => {$(
+        impl OffsetInt for $t {
+            const ALIAS: &'static str = stringify!($alias);
+            const SIGNED: bool = <$t>::MIN != 0;
+
+            fn checked_sub_offset(self, k: i64) -> Option<$t> {
+                (self as i128)
+                    .checked_sub(i128::from(k))
+                    .and_then(|value| <$t>::try_from(value).ok())
+            }
+
+            fn checked_add_offset(self, k: i64) -> Option<$t> {
+                (self as i128)
+                    .checked_add(i128::from(k))
+                    .and_then(|value| <$t>::try_from(value).ok())
+            }
+        }
+    )*};
+}
+
+impl_offset_int!(
+    i8 => I8_1,
+    i16 => I16_1,
+    i32 => I32_1,
+    i64 => I64_1,
+    i128 => I128_1,
+    isize => Isize1,
+    u8 => U8_1,
+    u16 => U16_1,
+    u32 => U32_1,
+    u64 => U64_1,
+    usize => Usize1,
+);
 
-impl Readable for Usize1 {
-    type Output = usize;
-    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> usize {
-        // panic if the subtraction overflows
-        usize::read(source)
-            .checked_sub(1)
-            .expect("attempted to read the value 0 as a Usize1")
+impl OffsetInt for u128 {
+    const ALIAS: &'static str = "U128_1";
+    const SIGNED: bool = false;
+
+    fn checked_sub_offset(self, k: i64) -> Option<u128> {
+        match u128::try_from(k) {
+            Ok(k) => self.checked_sub(k),
+            Err(_) => self.checked_add(u128::from(k.unsigned_abs())),
+        }
+    }
+
+    fn checked_add_offset(self, k: i64) -> Option<u128> {
+        match u128::try_from(k) {
+            Ok(k) => self.checked_add(k),
+            Err(_) => self.checked_sub(u128::from(k.unsigned_abs())),
+        }
+    }
+}
+
+impl<T: OffsetInt, const K: i64> Readable for Offset<T, K> {
+    type Output = T;
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> T {
+        let value = T::read(source);
+        value.checked_sub_offset(K).unwrap_or_else(|| {
+            // the aliases like `Usize1` keep their own messages.
+            if K == 1 && T::SIGNED {
+                panic!(
+                    concat!(
+                        "attempted to read the value {} as a {}:",
+                        " the value is {}::MIN and cannot be decremented"
+                    ),
+                    value,
+                    T::ALIAS,
+                    type_name::<T>(),
+                )
+            } else if K == 1 {
+                panic!("attempted to read the value {} as a {}", value, T::ALIAS)
+            } else {
+                panic!(
+                    "attempted to read the value {} as a `Offset<{}, {}>`: the result overflows",
+                    value,
+                    type_name::<T>(),
+                    K,
+                )
+            }
+        })
     }
 }
 
+impl<T: OffsetInt, const K: i64> Writable for Offset<T, K> {
+    type Value = T;
+    const SEP: &'static str = " ";
+    fn write(value: &T, buf: &mut Vec<u8>) {
+        value
+            .checked_add_offset(K)
+            .unwrap_or_else(|| {
+                if K == 1 {
+                    panic!(
+                        "attempted to write {}::MAX as a {}",
+                        type_name::<T>(),
+                        T::ALIAS
+                    )
+                } else {
+                    panic!(
+                        concat!(
+                            "attempted to write the value {} as a `Offset<{}, {}>`:",
+                            " the result overflows"
+                        ),
+                        value,
+                        type_name::<T>(),
+                        K,
+                    )
+                }
+            })
+            .fast_write(buf);
+    }
+}
+
+/// Usize1: 1-indexed usize.  Output of reading has type usize.
+pub type Usize1 = Offset<usize, 1>;
+
 /// Isize1: 1-indexed isize.  Output of reading has type isize.
-pub enum Isize1 {}
-
-impl Readable for Isize1 {
-    type Output = isize;
-    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> isize {
-        // FIXME: Which is appropriate, forbidding all negative values or only isize::MIN. For now
-        // we disallow only isize::MIN.
-        // ensure the value is more than isize::MIN, or subtract overflows.
-        isize::read(source).checked_sub(1).unwrap_or_else(|| {
-            panic!(
-                concat!(
-                    "attempted to read the value {} as a Isize1:",
-                    " the value is isize::MIN and cannot be decremented"
-                ),
-                isize::MIN,
-            )
+pub type Isize1 = Offset<isize, 1>;
+
+/// Usize0: 0-indexed usize, which is read as is.  Output of reading has type usize.
+pub type Usize0 = Offset<usize, 0>;
+
+/// UsizeMinus: 0-indexed usize converted to 1-indexed.  Output of reading has type usize.
+pub type UsizeMinus = Offset<usize, -1>;
+
+macro_rules! offset_aliases {
+    ($($(#[$attr:meta])* $name:ident = $t:ty;)*) => {$(
+        $(#[$attr])*
+        #[allow(non_camel_case_types)]
+        pub type $name = Offset<$t, 1>;
+    )*};
+}
+
+offset_aliases! {
+    /// U8_1: 1-indexed u8.  Output of reading has type u8.
+    U8_1 = u8;
+    /// U16_1: 1-indexed u16.  Output of reading has type u16.
+    U16_1 = u16;
+    /// U32_1: 1-indexed u32.  Output of reading has type u32.
+    U32_1 = u32;
+    /// U64_1: 1-indexed u64.  Output of reading has type u64.
+    U64_1 = u64;
+    /// U128_1: 1-indexed u128.  Output of reading has type u128.
+    U128_1 = u128;
+    /// I8_1: 1-indexed i8.  Output of reading has type i8.
+    I8_1 = i8;
+    /// I16_1: 1-indexed i16.  Output of reading has type i16.
+    I16_1 = i16;
+    /// I32_1: 1-indexed i32.  Output of reading has type i32.
+    I32_1 = i32;
+    /// I64_1: 1-indexed i64.  Output of reading has type i64.
+    I64_1 = i64;
+    /// I128_1: 1-indexed i128.  Output of reading has type i128.
+    I128_1 = i128;
+}
+
+/// Parse<T>: read a token and parse it by `FromStr`.  Output of reading has type `T`.
+///
+/// `input!` reads a type implementing `FromStr` by itself, but only the primitives and `String`
+/// are `Readable`.  Use this where a `Readable` type is required, like a member of
+/// `#[derive_readable]` or an element of a tuple type argument.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::marker::Parse;
+/// use proconio::source::auto::AutoSource;
+/// use std::net::Ipv4Addr;
+///
+/// let source = AutoSource::from("127.0.0.1");
+/// input! {
+///     from source,
+///     addr: Parse<Ipv4Addr>,
+/// }
+///
+/// assert_eq!(addr, Ipv4Addr::LOCALHOST);
+/// ```
+pub struct Parse<T>(PhantomData<fn() -> T>);
+
+impl<T: FromStr> Readable for Parse<T>
+where
+    T::Err: Debug,
+{
+    type Output = T;
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> T {
+        parse_token(source)
+    }
+}
+
+/// LenPrefixed<T, L>: read a length by `L`, and then that many values of `T`.  Output of reading
+/// has type `Vec<T::Output>`.
+///
+/// This is the type-level version of `[T]` in `input!`, so it can be a member of
+/// `#[derive_readable]` or a type argument.  Writing is the same as `[T]` in `output!`: the length
+/// on its own line, followed by the line of the values, which is empty if there are no values.
+///
+/// The length is read by `L`, which defaults to `usize`.  Note that `L` converts the length
+/// itself, not the indices in `T`: `LenPrefixed<T, Usize1>` reads `n` followed by `n - 1` values,
+/// like the edges of a tree following the number of the vertices.  Writing adds 1 back, so it
+/// writes `n` for `n - 1` values.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::marker::{LenPrefixed, Usize1};
+/// use proconio::source::auto::AutoSource;
+///
+/// let source = AutoSource::from("2 3 1 2 3 1 4  3 1 2 2 3");
+/// input! {
+///     from source,
+///     rows: LenPrefixed<LenPrefixed<i32>>,
+///     // 3 vertices, followed by 3 - 1 = 2 edges
+///     edges: LenPrefixed<(Usize1, Usize1), Usize1>,
+/// }
+///
+/// assert_eq!(rows, [vec![1, 2, 3], vec![4]]);
+/// assert_eq!(edges, [(0, 1), (1, 2)]);
+/// ```
+pub struct LenPrefixed<T, L = usize>(PhantomData<fn() -> (T, L)>);
+
+impl<T: Readable, L: Readable<Output = usize>> Readable for LenPrefixed<T, L> {
+    type Output = Vec<T::Output>;
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Vec<T::Output> {
+        let len = L::read(source);
+        (0..len).map(|_| T::read(source)).collect()
+    }
+}
+
+impl<T: Writable, L: Writable<Value = usize>> Writable for LenPrefixed<T, L>
+where
+    T::Value: Sized,
+{
+    type Value = Vec<T::Value>;
+    const SEP: &'static str = "\n";
+    fn write(value: &Vec<T::Value>, buf: &mut Vec<u8>) {
+        // the same as `[T]` in `output!`, which writes the line of the values even if it is empty.
+        L::write(&value.len(), buf);
+        buf.push(b'\n');
+        write_joined::<T, _>(value, buf);
+    }
+}
+
+/// Optional<T, SENTINEL>: read `None` if the token is `SENTINEL`, or `Some` of the value of `T`
+/// otherwise.  Output of reading has type `Option<T::Output>`.
+///
+/// `SENTINEL` defaults to `-1`, which is often used for "no such value".  `T` can read more than
+/// one token, like `Optional<(Usize1, i64)>` reading `-1` or a pair starting with a non-sentinel
+/// token.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::marker::{Optional, Usize1};
+/// use proconio::source::auto::AutoSource;
+///
+/// let source = AutoSource::from("-1 1 2 0 5");
+/// input! {
+///     from source,
+///     parent: [Optional<Usize1>; 3],
+///     next: [Optional<u32, 0>; 2],
+/// }
+///
+/// assert_eq!(parent, [None, Some(0), Some(1)]);
+/// assert_eq!(next, [None, Some(5)]);
+/// ```
+pub struct Optional<T, const SENTINEL: i64 = -1>(PhantomData<fn() -> T>);
+
+impl<T: Readable, const SENTINEL: i64> Readable for Optional<T, SENTINEL> {
+    type Output = Option<T::Output>;
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Option<T::Output> {
+        let token = source.next_token_unwrap();
+        if token.parse::<i64>() == Ok(SENTINEL) {
+            return None;
+        }
+
+        // `T` reads the token again, followed by the rest of the source
+        let mut source = Prepended {
+            token: token.to_owned(),
+            taken: false,
+            rest: source,
+        };
+        Some(T::read::<R, _>(&mut source))
+    }
+}
+
+/// A source giving out a token which is already read, followed by the tokens of `rest`.
+struct Prepended<'a, S> {
+    token: String,
+    taken: bool,
+    rest: &'a mut S,
+}
+
+impl<R: BufRead, S: Source<R>> Source<R> for Prepended<'_, S> {
+    fn next_token(&mut self) -> Option<&str> {
+        if self.taken {
+            self.rest.next_token()
+        } else {
+            self.taken = true;
+            Some(&self.token)
+        }
+    }
+
+    fn is_empty(&mut self) -> bool {
+        self.taken && self.rest.is_empty()
+    }
+}
+
+/// Collect<C, T>: read a sequence by `T`, and collect it into `C`.  Output of reading has type
+/// `C`.
+///
+/// This is the type-level version of `[T; n] as C` in `input!`, like `Collect<BTreeSet<i64>,
+/// LenPrefixed<i64>>`.  Unlike `as C`, the type arguments of `C` must be given explicitly.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::marker::{Collect, LenPrefixed};
+/// use proconio::source::auto::AutoSource;
+/// use std::collections::BTreeSet;
+///
+/// let source = AutoSource::from("3 2 1 2");
+/// input! {
+///     from source,
+///     set: Collect<BTreeSet<i64>, LenPrefixed<i64>>,
+/// }
+///
+/// assert_eq!(set, BTreeSet::from([1, 2]));
+/// ```
+pub struct Collect<C, T>(PhantomData<fn() -> (C, T)>);
+
+impl<C, T> Readable for Collect<C, T>
+where
+    T: Readable,
+    T::Output: IntoIterator,
+    C: FromIterator<<T::Output as IntoIterator>::Item>,
+{
+    type Output = C;
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> C {
+        T::read(source).into_iter().collect()
+    }
+}
+
+/// Matrix<T>: read a matrix of `height` rows and `width` columns of `T` into a contiguous
+/// [`Grid`].  Output of reading has type `Grid<T::Output>`.
+///
+/// `[[T; w]; h] as Matrix` in `input!` reads the same grid.  Writing a grid writes each row in
+/// its own line.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::marker::Matrix;
+/// use proconio::source::auto::AutoSource;
+///
+/// let source = AutoSource::from("2 2 1 2 3 4");
+/// input! {
+///     from source,
+///     h: usize,
+///     w: usize,
+///     a: with Matrix::<i64>::new(h, w),
+/// }
+///
+/// assert_eq!(a.row(1), [3, 4]);
+/// ```
+pub struct Matrix<T> {
+    height: usize,
+    width: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Matrix<T> {
+    pub fn new(height: usize, width: usize) -> Self {
+        Matrix {
+            height,
+            width,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Readable> RuntimeReadable for Matrix<T> {
+    type Output = Grid<T::Output>;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> Grid<T::Output> {
+        Grid::from_fn(self.height, self.width, |_, _| T::read(source))
+    }
+}
+
+impl<T: Writable> Writable for Matrix<T>
+where
+    T::Value: Sized,
+{
+    type Value = Grid<T::Value>;
+    const SEP: &'static str = "\n";
+    fn write(value: &Grid<T::Value>, buf: &mut Vec<u8>) {
+        for (i, row) in value.rows().enumerate() {
+            if i != 0 {
+                buf.push(b'\n');
+            }
+            write_joined::<T, _>(row, buf);
+        }
+    }
+}
+
+/// CharGrid: read `height` rows of `Chars` into a [`Grid<char>`].
+///
+/// The width is given by [`with_width`](CharGrid::with_width), or taken from the first row
+/// otherwise.  The rows are either contiguous like `#.#`, or split by spaces like `# . #` if the
+/// width is given, which is decided by the first row.  It panics if a row has a different width,
+/// showing the row with its 1-indexed number.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::marker::CharGrid;
+/// use proconio::source::auto::AutoSource;
+///
+/// let source = AutoSource::from("2 3\nS.#\n#.G\n2 2\n. #\n# .");
+/// input! {
+///     from source,
+///     h: usize,
+///     w: usize,
+///     maze: with CharGrid::new(h),
+///     h2: usize,
+///     w2: usize,
+///     split: with CharGrid::with_width(h2, w2),
+/// }
+///
+/// assert_eq!(maze.find(&'S'), Some((0, 0)));
+/// assert_eq!(maze.find(&'G'), Some((1, 2)));
+/// assert_eq!(split.row(1), ['#', '.']);
+/// ```
+pub struct CharGrid {
+    height: usize,
+    width: Option<usize>,
+}
+
+impl CharGrid {
+    pub fn new(height: usize) -> Self {
+        CharGrid {
+            height,
+            width: None,
+        }
+    }
+
+    pub fn with_width(height: usize, width: usize) -> Self {
+        CharGrid {
+            height,
+            width: Some(width),
+        }
+    }
+}
+
+impl RuntimeReadable for CharGrid {
+    type Output = Grid<char>;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> Grid<char> {
+        read_rows(source, self.height, self.width, |token| {
+            token.chars().collect()
         })
     }
 }
+
+impl Writable for CharGrid {
+    type Value = Grid<char>;
+    const SEP: &'static str = "\n";
+    fn write(value: &Grid<char>, buf: &mut Vec<u8>) {
+        for (i, row) in value.rows().enumerate() {
+            if i != 0 {
+                buf.push(b'\n');
+            }
+            for c in row {
+                c.fast_write(buf);
+            }
+        }
+    }
+}
+
+/// ByteGrid: read `height` rows of `Bytes` into a [`Grid<u8>`].  See [`CharGrid`] for the format.
+pub struct ByteGrid {
+    height: usize,
+    width: Option<usize>,
+}
+
+impl ByteGrid {
+    pub fn new(height: usize) -> Self {
+        ByteGrid {
+            height,
+            width: None,
+        }
+    }
+
+    pub fn with_width(height: usize, width: usize) -> Self {
+        ByteGrid {
+            height,
+            width: Some(width),
+        }
+    }
+}
+
+impl RuntimeReadable for ByteGrid {
+    type Output = Grid<u8>;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> Grid<u8> {
+        read_rows(source, self.height, self.width, |token| {
+            token.bytes().collect()
+        })
+    }
+}
+
+impl Writable for ByteGrid {
+    type Value = Grid<u8>;
+    const SEP: &'static str = "\n";
+    fn write(value: &Grid<u8>, buf: &mut Vec<u8>) {
+        for (i, row) in value.rows().enumerate() {
+            if i != 0 {
+                buf.push(b'\n');
+            }
+            buf.extend_from_slice(row);
+        }
+    }
+}
+
+/// BoolGrid: read `height` rows of characters into a [`Grid<bool>`], where a cell is `true` if it
+/// is the character `wall`.  See [`CharGrid`] for the format.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::marker::BoolGrid;
+/// use proconio::source::auto::AutoSource;
+///
+/// let source = AutoSource::from("2\n.#\n#.");
+/// input! {
+///     from source,
+///     h: usize,
+///     wall: with BoolGrid::new(h, '#'),
+/// }
+///
+/// assert_eq!(wall.as_slice(), [false, true, true, false]);
+/// ```
+pub struct BoolGrid {
+    height: usize,
+    width: Option<usize>,
+    wall: char,
+}
+
+impl BoolGrid {
+    pub fn new(height: usize, wall: char) -> Self {
+        BoolGrid {
+            height,
+            width: None,
+            wall,
+        }
+    }
+
+    pub fn with_width(height: usize, width: usize, wall: char) -> Self {
+        BoolGrid {
+            height,
+            width: Some(width),
+            wall,
+        }
+    }
+}
+
+impl RuntimeReadable for BoolGrid {
+    type Output = Grid<bool>;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> Grid<bool> {
+        let wall = self.wall;
+        read_rows(source, self.height, self.width, |token| {
+            token.chars().map(|c| c == wall).collect()
+        })
+    }
+}
+
+/// Reads the rows of a grid, each of which is a token or the tokens of single cells split by
+/// spaces.
+///
+/// Whether the rows are split is decided once by the first row: they are split only if the width
+/// is given, it is more than 1, and the first token is a single cell.  A malformed row in the
+/// other rows is reported by a panic instead of changing the format.
+fn read_rows<T, R: BufRead, S: Source<R>>(
+    source: &mut S,
+    height: usize,
+    width: Option<usize>,
+    cells: impl Fn(&str) -> Vec<T>,
+) -> Grid<T> {
+    let mut width = width;
+    let mut split = false;
+    let mut data = Vec::new();
+    for i in 0..height {
+        let token = source.next_token_unwrap();
+        let mut row = cells(token);
+        let mut line = token.to_string();
+        let w = *width.get_or_insert(row.len());
+        if i == 0 {
+            split = row.len() == 1 && w > 1;
+        }
+        if split {
+            // the row split by spaces like `# . #`
+            for _ in 1..w {
+                let token = source.next_token_unwrap();
+                row.extend(cells(token));
+                line.push(' ');
+                line.push_str(token);
+            }
+        }
+
+        if row.len() != w {
+            panic!(
+                "the row {} `{}` has {} cells, but the width of the grid is {}",
+                i + 1,
+                line,
+                row.len(),
+                w
+            );
+        }
+        data.extend(row);
+    }
+
+    Grid::from_vec(height, width.unwrap_or(0), data)
+}
+
+/// Fixed<P>: write a floating point number with `P` digits after the decimal point.  Only for
+/// output.  To print a value with `print!`, use [`out::fixed()`](crate::out::fixed) instead.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::marker::Fixed;
+/// use proconio::output;
+///
+/// let mut out = Vec::new();
+/// output! {
+///     to &mut out,
+///     2.0 / 3.0: Fixed<6>,
+/// }
+///
+/// assert_eq!(out, b"0.666667\n");
+/// ```
+pub enum Fixed<const P: usize> {}
+
+impl<const P: usize> Writable for Fixed<P> {
+    type Value = f64;
+    const SEP: &'static str = " ";
+    fn write(value: &f64, buf: &mut Vec<u8>) {
+        crate::out::fixed(*value, P).fast_write(buf);
+    }
+}
@@ -0,0 +1,461 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Buffered stdout shared by the whole program.
+//!
+//! This module provides `print!` and `println!` writing into a global buffer instead of stdout.
+//! They can be used anywhere, including helper functions, closures and other threads.  This is the
+//! same buffer as the one `print!` and `println!` in a `#[fastout]` function write into, so the
+//! order of the output is kept across the whole program.
+//!
+//! ```
+//! use proconio::out::{print, println};
+//!
+//! fn print_path(path: &[usize]) {
+//!     for v in path {
+//!         print!("{} ", v + 1);
+//!     }
+//!     println!();
+//! }
+//!
+//! fn main() {
+//!     println!("{}", 3);
+//!     print_path(&[0, 2, 1]);
+//! }
+//! ```
+//!
+//! The buffer is written to stdout when it becomes full, when [`Stdout::flush()`] is called, when
+//! a `#[fastout]` function exits, and when the program exits.  The last one includes the exit by
+//! `std::process::exit()` and by a panic in the main thread.  Note that the buffer is not written
+//! if the program is aborted, such as by `panic = "abort"`, unless [`flush_on_panic()`] is called.
+//!
+//! Output written directly to `std::io::stdout()`, for example by `std::println!`, does not go
+//! through this buffer.  Mixing them may change the order of the output.
+
+mod fast;
+
+pub use self::fast::{fixed, FastWrite, Precision};
+
+#[doc(hidden)]
+pub use self::fast::{ViaDisplay as __ViaDisplay, ViaFastWrite as __ViaFastWrite, Wrap as __Wrap};
+
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::mem;
+use std::panic;
+use std::sync::{Mutex, MutexGuard, Once, OnceLock, PoisonError, TryLockError};
+use std::thread;
+
+/// The default capacity of the buffer, which is the same as `std::io::BufWriter`.
+const DEFAULT_CAPACITY: usize = 8 * 1024;
+
+static STDOUT: OnceLock<Mutex<Buffer>> = OnceLock::new();
+
+fn lock() -> MutexGuard<'static, Buffer> {
+    STDOUT
+        .get_or_init(|| {
+            register_flush_at_exit();
+            Mutex::new(Buffer {
+                buf: Vec::with_capacity(DEFAULT_CAPACITY),
+                capacity: DEFAULT_CAPACITY,
+                sink: Sink::Stdout,
+                flush: Flush::Auto,
+            })
+        })
+        .lock()
+        // A panic while writing never leaves the buffer in an invalid state.
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Where the buffered output is written to.
+#[doc(hidden)]
+#[derive(Debug)]
+pub enum Sink {
+    Stdout,
+    Stderr,
+    File(File),
+}
+
+impl Sink {
+    /// Creates a file sink.  Used by `#[fastout(sink = file("out.txt"))]`.
+    #[doc(hidden)]
+    pub fn __file<P: AsRef<std::path::Path>>(path: P) -> Sink {
+        let path = path.as_ref();
+        match File::create(path) {
+            Ok(file) => Sink::File(file),
+            Err(e) => panic!("failed to create the output file {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Stdout => io::stdout().write(buf),
+            Sink::Stderr => io::stderr().write(buf),
+            Sink::File(file) => file.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Sink::Stdout => io::stdout().lock().write_all(buf),
+            Sink::Stderr => io::stderr().lock().write_all(buf),
+            Sink::File(file) => file.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Stdout => io::stdout().flush(),
+            Sink::Stderr => io::stderr().flush(),
+            Sink::File(file) => file.flush(),
+        }
+    }
+}
+
+/// When the buffered output is written to the sink.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flush {
+    /// When the buffer becomes full.
+    Auto,
+
+    /// After each write containing a newline.
+    Line,
+
+    /// Only when flushed explicitly.  The buffer grows as needed.
+    Manual,
+}
+
+struct Buffer {
+    buf: Vec<u8>,
+    capacity: usize,
+    sink: Sink,
+    flush: Flush,
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.flush == Flush::Auto && self.buf.len() + buf.len() > self.capacity {
+            self.flush()?;
+            if buf.len() >= self.capacity {
+                // Too large to buffer: write it directly.
+                return self.sink.write_all(buf);
+            }
+        }
+
+        self.buf.extend_from_slice(buf);
+
+        if self.flush == Flush::Line && buf.contains(&b'\n') {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // The buffer is cleared even on an error, so that the same output is not written twice.
+        let res = self.sink.write_all(&self.buf);
+        self.buf.clear();
+        res?;
+        self.sink.flush()
+    }
+}
+
+/// Replaces the configuration of the buffer until the returned guard is dropped.  Used by the
+/// attribute options of `#[fastout]`, like `#[fastout(capacity = 1 << 20, sink = stderr)]`.
+///
+/// The output buffered so far is written to the previous sink first.
+#[doc(hidden)]
+pub fn __configure(
+    capacity: Option<usize>,
+    sink: Option<Sink>,
+    flush: Option<Flush>,
+) -> ConfigGuard {
+    let mut buffer = lock();
+    if let Err(e) = buffer.flush() {
+        panic!("failed to flush stdout: {}", e);
+    }
+
+    let prev = ConfigGuard {
+        capacity: capacity.map(|capacity| mem::replace(&mut buffer.capacity, capacity)),
+        sink: sink.map(|sink| mem::replace(&mut buffer.sink, sink)),
+        flush: flush.map(|flush| mem::replace(&mut buffer.flush, flush)),
+    };
+
+    let reserve = buffer.capacity.saturating_sub(buffer.buf.capacity());
+    buffer.buf.reserve(reserve);
+
+    prev
+}
+
+/// Restores the previous configuration of the buffer when dropped.
+#[doc(hidden)]
+pub struct ConfigGuard {
+    capacity: Option<usize>,
+    sink: Option<Sink>,
+    flush: Option<Flush>,
+}
+
+impl Drop for ConfigGuard {
+    fn drop(&mut self) {
+        let mut buffer = lock();
+
+        // Errors are reported by the flush on the exit of the function, which runs before this.
+        let _ = buffer.flush();
+
+        if let Some(capacity) = self.capacity.take() {
+            buffer.capacity = capacity;
+        }
+        if let Some(sink) = self.sink.take() {
+            buffer.sink = sink;
+        }
+        if let Some(flush) = self.flush.take() {
+            buffer.flush = flush;
+        }
+    }
+}
+
+#[cfg(any(unix, windows))]
+fn register_flush_at_exit() {
+    use std::os::raw::c_int;
+
+    extern "C" {
+        fn atexit(callback: extern "C" fn()) -> c_int;
+    }
+
+    extern "C" fn flush_at_exit() {
+        // Don't wait for the lock: another thread may be stopped while holding it.
+        if let Some(stdout) = STDOUT.get() {
+            let mut stdout = match stdout.try_lock() {
+                Ok(stdout) => stdout,
+                Err(TryLockError::Poisoned(e)) => e.into_inner(),
+                Err(TryLockError::WouldBlock) => return,
+            };
+            let _ = stdout.flush();
+        }
+    }
+
+    // # Safety
+    //
+    // `atexit` is provided by the C runtime, which the standard library links on these platforms.
+    // `flush_at_exit` neither panics nor unwinds across the FFI boundary.
+    unsafe {
+        atexit(flush_at_exit);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn register_flush_at_exit() {}
+
+/// Writes formatted output into the buffered stdout.
+///
+/// This is a drop-in replacement for `std::print!`.  See [the module documentation](self) for
+/// details.
+#[doc(inline)]
+pub use crate::__out_print as print;
+
+/// Writes formatted output followed by a newline into the buffered stdout.
+///
+/// This is a drop-in replacement for `std::println!`.  See [the module documentation](self) for
+/// details.
+#[doc(inline)]
+pub use crate::__out_println as println;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __out_print {
+    ($($tt:tt)*) => {{
+        ::std::write!($crate::out::stdout(), $($tt)*).unwrap();
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __out_println {
+    ($($tt:tt)*) => {{
+        ::std::writeln!($crate::out::stdout(), $($tt)*).unwrap();
+    }};
+}
+
+/// A handle to the buffered stdout.
+///
+/// The handle itself holds nothing, so it is `Send` and `Sync`.  Each write locks the buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct Stdout(());
+
+/// Gets a handle to the buffered stdout.
+///
+/// The handle implements `std::io::Write`, so you can use it with `write!` and `writeln!`.
+///
+/// ```
+/// use proconio::out::stdout;
+/// use std::io::Write;
+///
+/// let mut out = stdout();
+/// writeln!(out, "{} {}", 1, 2).unwrap();
+/// out.flush().unwrap();
+/// ```
+pub fn stdout() -> Stdout {
+    Stdout(())
+}
+
+impl Stdout {
+    /// Returns the handle itself.
+    ///
+    /// This exists for compatibility with `std::io::Stdout::lock()`, since `#[fastout]` rewrites
+    /// `std::io::stdout()` into [`stdout()`].  Unlike the standard library, the buffer is not kept
+    /// locked, and it is locked on each write instead.
+    pub fn lock(&self) -> Stdout {
+        *self
+    }
+
+    /// Writes formatted output.  The whole output is written while the buffer is locked once, so
+    /// that the output is not mixed with the output from another thread.
+    ///
+    /// The arguments are formatted before the buffer is locked, so a `Display` implementation can
+    /// print something by itself.
+    pub fn write_fmt(&mut self, args: fmt::Arguments) -> io::Result<()> {
+        write_formatted(|buf| buf.write_fmt(args))
+    }
+
+    /// Writes a value by [`FastWrite`], which is faster than `write!(out, "{}", value)`.
+    ///
+    /// ```
+    /// use proconio::out::{fixed, stdout};
+    ///
+    /// let mut out = stdout();
+    /// out.write_value(&123456789).unwrap();
+    /// out.write_value(" ").unwrap();
+    /// out.write_value(&fixed(0.5, 3)).unwrap();
+    /// out.write_value("\n").unwrap();
+    /// ```
+    pub fn write_value<T: FastWrite + ?Sized>(&mut self, value: &T) -> io::Result<()> {
+        write_formatted(|buf| {
+            value.fast_write(buf);
+            Ok(())
+        })
+    }
+
+    /// Writes the buffered output to stdout.
+    pub fn flush(&mut self) -> io::Result<()> {
+        lock().flush()
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        lock().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        lock().write_all(buf)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments) -> io::Result<()> {
+        Stdout::write_fmt(self, args)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Stdout::flush(self)
+    }
+}
+
+/// Writes the output directly into the buffer by `f`.  Used by the fast path of `print!` in
+/// `#[fastout]` function.
+#[doc(hidden)]
+pub fn __write_with<F: FnOnce(&mut Vec<u8>)>(f: F) -> io::Result<()> {
+    write_formatted(|buf| {
+        f(buf);
+        Ok(())
+    })
+}
+
+/// Formats the output into a scratch buffer by `f`, and then writes it into the buffered stdout.
+///
+/// The buffered stdout is not locked while `f` runs, since `f` calls `Display` or `FastWrite`
+/// implementations of the user, which may print something by themselves.  The lock is not
+/// reentrant, so locking it there would deadlock.
+fn write_formatted<F: FnOnce(&mut Vec<u8>) -> io::Result<()>>(f: F) -> io::Result<()> {
+    thread_local! {
+        static SCRATCH: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    // Take the scratch buffer out, so that a nested print in `f` uses a new one.
+    let mut scratch = SCRATCH.with(|scratch| mem::take(&mut *scratch.borrow_mut()));
+    let res = f(&mut scratch).and_then(|()| lock().write_all(&scratch));
+    scratch.clear();
+    SCRATCH.with(|slot| *slot.borrow_mut() = scratch);
+
+    res
+}
+
+/// Writes the buffered output to stdout before the panic message is printed, whenever a thread
+/// panics.
+///
+/// By default, the buffered output is written after the panic message when the panic unwinds
+/// through a `#[fastout]` function or exits the program, and it is lost if the panic aborts the
+/// process.  After calling this function, the output printed before the panic is always written
+/// first, even with `panic = "abort"`.  This is also available as `#[fastout(flush_on_panic)]`.
+///
+/// This installs a panic hook wrapping the current one.  Calling this function more than once has
+/// no additional effect.
+pub fn flush_on_panic() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let prev = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // Don't wait for the lock: the panicking thread itself may hold it, for example when a
+            // `Display` implementation panics while printing.
+            if let Some(stdout) = STDOUT.get() {
+                let stdout = match stdout.try_lock() {
+                    Ok(stdout) => Some(stdout),
+                    Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+                    Err(TryLockError::WouldBlock) => None,
+                };
+                if let Some(mut stdout) = stdout {
+                    let _ = stdout.flush();
+                }
+            }
+            prev(info);
+        }));
+    });
+}
+
+/// Flushes the buffered stdout when dropped, so that the output is written even if the function
+/// exits by an early `return`, `?` or a panic.
+///
+/// If flushing fails, this panics to report the error, unless the thread is already panicking.
+#[doc(hidden)]
+pub struct FlushOnDrop(());
+
+#[doc(hidden)]
+pub fn __flush_on_drop() -> FlushOnDrop {
+    FlushOnDrop(())
+}
+
+impl Drop for FlushOnDrop {
+    fn drop(&mut self) {
+        let res = stdout().flush();
+
+        // Panicking while panicking aborts the process, so the error is ignored in that case.
+        if let Err(e) = res {
+            if !thread::panicking() {
+                panic!("failed to flush stdout: {}", e);
+            }
+        }
+    }
+}
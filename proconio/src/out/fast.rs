@@ -0,0 +1,255 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Formatting without `core::fmt`.
+
+use std::fmt::{self, Display};
+use std::io::Write;
+
+/// Types which can be written into a byte buffer directly, without `core::fmt`.
+///
+/// The output must be the same as the one of `Display` with no formatting options.  Integers are
+/// formatted two digits at a time using a table, which is much faster than `core::fmt` when
+/// printing a large number of integers.
+///
+/// `print!` and `println!` in a `#[fastout]` function use this trait when the format string is
+/// `"{}"` or `{}`s joined by spaces like `"{} {} {}"`.  Types not implementing this trait are
+/// written by `Display` as usual.
+pub trait FastWrite {
+    /// Appends the value to the buffer.
+    fn fast_write(&self, buf: &mut Vec<u8>);
+}
+
+impl<T: FastWrite + ?Sized> FastWrite for &T {
+    fn fast_write(&self, buf: &mut Vec<u8>) {
+        T::fast_write(*self, buf)
+    }
+}
+
+impl<T: FastWrite + ?Sized> FastWrite for &mut T {
+    fn fast_write(&self, buf: &mut Vec<u8>) {
+        T::fast_write(*self, buf)
+    }
+}
+
+impl FastWrite for str {
+    fn fast_write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl FastWrite for String {
+    fn fast_write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl FastWrite for char {
+    fn fast_write(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+}
+
+/// `"00"`, `"01"`, ..., `"99"` concatenated.
+const DIGIT_PAIRS: [u8; 200] = {
+    let mut table = [0; 200];
+    let mut i = 0;
+    while i < 100 {
+        table[2 * i] = b'0' + (i / 10) as u8;
+        table[2 * i + 1] = b'0' + (i % 10) as u8;
+        i += 1;
+    }
+    table
+};
+
+/// Writes the digits of `n` into the end of `tmp`, and returns the index of the first digit.  At
+/// least `min_digits` digits are written, padded with zeros.
+fn write_digits(tmp: &mut [u8; 20], mut n: u64, min_digits: usize) -> usize {
+    let mut pos = tmp.len();
+    while n >= 100 {
+        let d = (n % 100) as usize * 2;
+        n /= 100;
+        pos -= 2;
+        tmp[pos..pos + 2].copy_from_slice(&DIGIT_PAIRS[d..d + 2]);
+    }
+    if n >= 10 {
+        let d = n as usize * 2;
+        pos -= 2;
+        tmp[pos..pos + 2].copy_from_slice(&DIGIT_PAIRS[d..d + 2]);
+    } else {
+        pos -= 1;
+        tmp[pos] = b'0' + n as u8;
+    }
+
+    while tmp.len() - pos < min_digits {
+        pos -= 1;
+        tmp[pos] = b'0';
+    }
+
+    pos
+}
+
+fn write_u64(buf: &mut Vec<u8>, n: u64, min_digits: usize) {
+    let mut tmp = [0; 20];
+    let pos = write_digits(&mut tmp, n, min_digits);
+    buf.extend_from_slice(&tmp[pos..]);
+}
+
+fn write_u128(buf: &mut Vec<u8>, n: u128) {
+    const TEN_19: u128 = 10_000_000_000_000_000_000;
+
+    if n <= u64::MAX as u128 {
+        write_u64(buf, n as u64, 1);
+    } else {
+        write_u128(buf, n / TEN_19);
+        write_u64(buf, (n % TEN_19) as u64, 19);
+    }
+}
+
+macro_rules! impl_fast_write_for_ints {
+    ($(($($unsigned:ty),*; $($signed:ty),*; $write:ident, $wide:ty)),*) => {$(
+        $(
+            impl FastWrite for $unsigned {
+                fn fast_write(&self, buf: &mut Vec<u8>) {
+                    $write(buf, *self as $wide);
+                }
+            }
+        )*
+        $(
+            impl FastWrite for $signed {
+                fn fast_write(&self, buf: &mut Vec<u8>) {
+                    if *self < 0 {
+                        buf.push(b'-');
+                    }
+                    $write(buf, self.unsigned_abs() as $wide);
+                }
+            }
+        )*
+    )*};
+}
+
+fn write_u64_min1(buf: &mut Vec<u8>, n: u64) {
+    write_u64(buf, n, 1);
+}
+
+impl_fast_write_for_ints!(
+    (u8, u16, u32, u64, usize; i8, i16, i32, i64, isize; write_u64_min1, u64),
+    (u128; i128; write_u128, u128)
+);
+
+/// A floating point number formatted with a fixed number of digits after the decimal point.
+///
+/// `Precision` implements both `Display` and [`FastWrite`], so it can be printed by any `print!`,
+/// and is printed quickly by `print!` in `#[fastout]` function.  Use [`fixed()`] to create one.
+///
+/// This is the value version of [`marker::Fixed`](crate::marker::Fixed), which is the marker for
+/// `output!` and `#[derive_writable]`.
+///
+/// The fast path rounds the value scaled by the power of ten, so the last digit may differ from
+/// the one of `format!("{:.*}", precision, value)` when the value is very close to the middle of
+/// two candidates.  Values too large to be scaled exactly are formatted by `core::fmt`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Precision {
+    value: f64,
+    precision: usize,
+}
+
+/// Formats the value with `precision` digits after the decimal point.
+///
+/// ```
+/// use proconio::out::fixed;
+///
+/// assert_eq!(fixed(3.14159, 3).to_string(), "3.142");
+/// assert_eq!(fixed(-1.25, 1).to_string(), "-1.2");
+/// assert_eq!(fixed(2.0, 2).to_string(), "2.00");
+/// ```
+pub fn fixed(value: f64, precision: usize) -> Precision {
+    Precision { value, precision }
+}
+
+impl Precision {
+    /// Writes the value if it can be done exactly by the integer arithmetic.  Returns `false` if
+    /// it cannot.
+    fn write_fast(&self, buf: &mut Vec<u8>) -> bool {
+        // 10^17 fits in f64 exactly, and the scaled value must fit in 2^53 to be exact.
+        const MAX_PRECISION: usize = 17;
+        const MAX_SCALED: f64 = (1u64 << 53) as f64;
+
+        if !self.value.is_finite() || self.precision > MAX_PRECISION {
+            return false;
+        }
+
+        let scale = 10u64.pow(self.precision as u32);
+        // `core::fmt` rounds ties to even as well.
+        let scaled = (self.value.abs() * scale as f64).round_ties_even();
+        if scaled >= MAX_SCALED {
+            return false;
+        }
+
+        let scaled = scaled as u64;
+        if self.value.is_sign_negative() {
+            buf.push(b'-');
+        }
+        write_u64(buf, scaled / scale, 1);
+        if self.precision > 0 {
+            buf.push(b'.');
+            write_u64(buf, scaled % scale, self.precision);
+        }
+
+        true
+    }
+}
+
+impl FastWrite for Precision {
+    fn fast_write(&self, buf: &mut Vec<u8>) {
+        if !self.write_fast(buf) {
+            write!(buf, "{:.*}", self.precision, self.value).unwrap();
+        }
+    }
+}
+
+impl Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = Vec::new();
+        self.fast_write(&mut buf);
+        f.write_str(std::str::from_utf8(&buf).expect(concat!(
+            "`Precision` produced invalid UTF-8.  ",
+            "This is a bug in `proconio`.  ",
+            "Please report this issue from ",
+            "<https://github.com/statiolake/proconio-rs/issues>."
+        )))
+    }
+}
+
+// Autoref specialization used by `#[fastout]` to choose `FastWrite` if available, and `Display`
+// otherwise.  `(&&Wrap(&value)).__proconio_write(buf)` finds `ViaFastWrite` first since its
+// receiver type `&&Wrap` matches without auto-deref.
+
+#[doc(hidden)]
+pub struct Wrap<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ViaFastWrite {
+    fn __proconio_write(&self, buf: &mut Vec<u8>);
+}
+
+impl<T: FastWrite + ?Sized> ViaFastWrite for &Wrap<'_, T> {
+    fn __proconio_write(&self, buf: &mut Vec<u8>) {
+        self.0.fast_write(buf);
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaDisplay {
+    fn __proconio_write(&self, buf: &mut Vec<u8>);
+}
+
+impl<T: Display + ?Sized> ViaDisplay for Wrap<'_, T> {
+    fn __proconio_write(&self, buf: &mut Vec<u8>) {
+        write!(buf, "{}", self.0).unwrap();
+    }
+}
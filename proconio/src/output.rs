@@ -0,0 +1,464 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+//! Implements `Writable`, the output counterpart of `Readable`, used by `output!` macro.
+//!
+//! A type implementing `Writable` knows how to write a value of `Writable::Value`.  Like
+//! `Readable`, the type can be different from the value: `Usize1` writes a `usize` with 1 added,
+//! and `Chars` writes a `Vec<char>` without spaces.
+
+use crate::out::FastWrite;
+use std::borrow::Borrow;
+use std::io::Write;
+
+/// The type which can be written by `output!` macro.
+///
+/// This is the output counterpart of [`Readable`](crate::source::Readable).
+pub trait Writable {
+    /// The type of the value written.
+    type Value: ?Sized;
+
+    /// The separator placed between the elements when this type is the element of an array.
+    ///
+    /// This is `" "` for scalars, and `"\n"` for types spanning multiple tokens, so that
+    /// `Vec<i32>` is written in a line and `Vec<Vec<i32>>` is written as rows.  It is `""` for types
+    /// writing nothing, like a struct without fields deriving `Writable`.
+    const SEP: &'static str;
+
+    /// Appends the value to the buffer.
+    fn write(value: &Self::Value, buf: &mut Vec<u8>);
+}
+
+macro_rules! impl_writable_via_fast_write {
+    ($($ty:ty),*) => {$(
+        impl Writable for $ty {
+            type Value = $ty;
+            const SEP: &'static str = " ";
+            fn write(value: &$ty, buf: &mut Vec<u8>) {
+                value.fast_write(buf);
+            }
+        }
+    )*};
+}
+
+impl_writable_via_fast_write!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, char, str, String
+);
+
+macro_rules! impl_writable_via_display {
+    ($($ty:ty),*) => {$(
+        impl Writable for $ty {
+            type Value = $ty;
+            const SEP: &'static str = " ";
+            fn write(value: &$ty, buf: &mut Vec<u8>) {
+                write!(buf, "{}", value).unwrap();
+            }
+        }
+    )*};
+}
+
+impl_writable_via_display!(f32, f64);
+
+/// `true` is written as `Yes` and `false` is written as `No`.
+impl Writable for bool {
+    type Value = bool;
+    const SEP: &'static str = " ";
+    fn write(value: &bool, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(if *value { b"Yes" } else { b"No" });
+    }
+}
+
+pub(crate) fn write_joined<'a, T, I>(values: I, buf: &mut Vec<u8>)
+where
+    T: Writable + ?Sized,
+    T::Value: 'a,
+    I: IntoIterator<Item = &'a T::Value>,
+{
+    for (i, value) in values.into_iter().enumerate() {
+        if i != 0 {
+            buf.extend_from_slice(T::SEP.as_bytes());
+        }
+        T::write(value, buf);
+    }
+}
+
+impl<T: Writable> Writable for Vec<T>
+where
+    T::Value: Sized,
+{
+    type Value = Vec<T::Value>;
+    const SEP: &'static str = "\n";
+    fn write(value: &Vec<T::Value>, buf: &mut Vec<u8>) {
+        write_joined::<T, _>(value, buf);
+    }
+}
+
+impl<T: Writable> Writable for [T]
+where
+    T::Value: Sized,
+{
+    type Value = [T::Value];
+    const SEP: &'static str = "\n";
+    fn write(value: &[T::Value], buf: &mut Vec<u8>) {
+        write_joined::<T, _>(value, buf);
+    }
+}
+
+impl<T: Writable, const N: usize> Writable for [T; N]
+where
+    T::Value: Sized,
+{
+    type Value = [T::Value; N];
+    const SEP: &'static str = "\n";
+    fn write(value: &[T::Value; N], buf: &mut Vec<u8>) {
+        write_joined::<T, _>(value, buf);
+    }
+}
+
+macro_rules! impl_writable_for_tuples {
+    ($(($($t:ident $v:ident),+))*) => {$(
+        /// The elements are separated by spaces.
+        impl<$($t: Writable),+> Writable for ($($t,)+)
+        where
+            $($t::Value: Sized),+
+        {
+            type Value = ($($t::Value,)+);
+            const SEP: &'static str = "\n";
+            #[allow(unused_assignments)]
+            fn write(value: &Self::Value, buf: &mut Vec<u8>) {
+                let ($($v,)+) = value;
+                let mut first = true;
+                $(
+                    if !first {
+                        buf.push(b' ');
+                    }
+                    first = false;
+                    $t::write($v, buf);
+                )+
+            }
+        }
+    )*};
+}
+
+impl_writable_for_tuples!(
+    (T1 v1)
+    (T1 v1, T2 v2)
+    (T1 v1, T2 v2, T3 v3)
+    (T1 v1, T2 v2, T3 v3, T4 v4)
+    (T1 v1, T2 v2, T3 v3, T4 v4, T5 v5)
+    (T1 v1, T2 v2, T3 v3, T4 v4, T5 v5, T6 v6)
+    (T1 v1, T2 v2, T3 v3, T4 v4, T5 v5, T6 v6, T7 v7)
+    (T1 v1, T2 v2, T3 v3, T4 v4, T5 v5, T6 v6, T7 v7, T8 v8)
+    (T1 v1, T2 v2, T3 v3, T4 v4, T5 v5, T6 v6, T7 v7, T8 v8, T9 v9)
+    (T1 v1, T2 v2, T3 v3, T4 v4, T5 v5, T6 v6, T7 v7, T8 v8, T9 v9, T10 v10)
+    (T1 v1, T2 v2, T3 v3, T4 v4, T5 v5, T6 v6, T7 v7, T8 v8, T9 v9, T10 v10, T11 v11)
+    (T1 v1, T2 v2, T3 v3, T4 v4, T5 v5, T6 v6, T7 v7, T8 v8, T9 v9, T10 v10, T11 v11, T12 v12)
+);
+
+// Helpers used in `output!` macro.  These must be public because they appear in macro-expanded
+// code, but hidden in doc because they are implementation details.
+
+/// Writes a value by the kind `K`.  `V` is `K::Value` or a reference to it.
+#[doc(hidden)]
+pub fn __write<K, V>(value: &V, buf: &mut Vec<u8>)
+where
+    K: Writable + ?Sized,
+    V: Borrow<K::Value> + ?Sized,
+{
+    K::write(value.borrow(), buf);
+}
+
+/// Writes a field of a struct deriving `Writable`.  `prev` is the `SEP` of the last field.  Fields
+/// are separated by a space if both of the neighbours have `SEP` of a space, and by a newline
+/// otherwise, so that a struct like `{ n: usize, a: Vec<i32> }` is written as `n` followed by the
+/// line of `a`, whatever the values are.  Fields with the empty `SEP` write nothing, and are
+/// skipped together with their separator.
+#[doc(hidden)]
+pub fn __write_field<K, V>(value: &V, prev: &mut Option<&'static str>, buf: &mut Vec<u8>)
+where
+    K: Writable + ?Sized,
+    V: Borrow<K::Value> + ?Sized,
+{
+    if K::SEP.is_empty() {
+        return;
+    }
+    if let Some(prev) = *prev {
+        buf.push(if prev == " " && K::SEP == " " {
+            b' '
+        } else {
+            b'\n'
+        });
+    }
+    K::write(value.borrow(), buf);
+    *prev = Some(K::SEP);
+}
+
+/// Writes a value by the kind inferred from its type, used for `_`.  Use it by the method syntax
+/// so that references are dereferenced automatically.
+#[doc(hidden)]
+pub trait __WriteValue {
+    fn __proconio_write(&self, buf: &mut Vec<u8>);
+    fn __proconio_sep(&self) -> &'static str;
+}
+
+impl<T: Writable<Value = T> + ?Sized> __WriteValue for T {
+    fn __proconio_write(&self, buf: &mut Vec<u8>) {
+        T::write(self, buf);
+    }
+
+    fn __proconio_sep(&self) -> &'static str {
+        T::SEP
+    }
+}
+
+/// A separator given by `sep`: a `char` or a string.
+#[doc(hidden)]
+pub trait __Separator {
+    fn __proconio_write_sep(&self, buf: &mut Vec<u8>);
+}
+
+impl __Separator for char {
+    fn __proconio_write_sep(&self, buf: &mut Vec<u8>) {
+        self.fast_write(buf);
+    }
+}
+
+impl __Separator for str {
+    fn __proconio_write_sep(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl __Separator for String {
+    fn __proconio_write_sep(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes());
+    }
+}
+
+/// Write values to the specified writer.
+///
+/// Basic syntax is:
+///
+/// ```text
+/// output! {
+///     to writer,      // optional: if you omitted, `proconio::out::stdout()` is used by default.
+///     value,          // written by the `Writable` implementation of its type.
+///     value: kind,    // written as the kind, like `Usize1` or `[Chars; h]`.
+///     ...
+/// }
+/// ```
+///
+/// The trailing comma is optional.  `writer` can be anything implementing `std::io::Write`.  This
+/// macro moves out the specified writer.  If you want to prevent moving, you can use `&mut writer`.
+///
+/// The grammar of kinds is the same as the one of `input!`, so a value read by `input!` is written
+/// back in the same format by `output!` with the same kind.  Each value is written on its own
+/// line.
+///
+/// - `[kind; n]` writes the elements of an array, which must have `n` elements.
+/// - `[kind]` writes the number of elements in a line first, and then the elements.
+/// - `(kind1, kind2, ...)` writes the elements of a tuple separated by spaces.
+/// - `_` writes the value by the `Writable` implementation of its type.
+/// - A type like `Usize1` writes the value as the type.
+///
+/// The elements of an array are separated by the separator of the element kind: spaces for
+/// scalars, and newlines for arrays, tuples and `Chars`.  Add `sep ' '` after the array kind to
+/// change it.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::marker::{Chars, Usize1};
+/// use proconio::output;
+///
+/// let n = 3;
+/// let a = vec![1, 2, 3];
+/// let p = vec![0, 2, 1];
+/// let grid = vec![vec!['#', '.'], vec!['.', '#']];
+///
+/// let mut out = Vec::new();
+/// output! {
+///     to &mut out,
+///     n,
+///     a: [_; n],
+///     p: [Usize1; n] sep ", ",
+///     grid: [Chars],
+///     (true, 0.5),
+/// }
+///
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "3\n1 2 3\n1, 3, 2\n2\n#.\n.#\nYes 0.5\n",
+/// );
+/// ```
+#[macro_export]
+macro_rules! output {
+    // terminator
+    (@buf [$buf:ident] @rest) => {};
+
+    // parse value until `:` or `,`
+    (@buf [$buf:ident] @rest $($rest:tt)+) => {
+        $crate::output!(@buf [$buf] @value [] @rest $($rest)*);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)+] @rest : $($rest:tt)*) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [] @rest $($rest)*);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)+] @rest , $($rest:tt)*) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [_] @rest , $($rest)*);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)+] @rest) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [_] @rest);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)*] @rest $tt:tt $($rest:tt)*) => {
+        $crate::output!(@buf [$buf] @value [$($value)* $tt] @rest $($rest)*);
+    };
+
+    // parse kind
+    (@buf [$buf:ident] @value [$($value:tt)*] @kind [] @rest [$($tt:tt)*] sep $sep:expr) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [[$($tt)*] sep $sep] @rest);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)*] @kind [] @rest [$($tt:tt)*] sep $sep:expr, $($rest:tt)*) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [[$($tt)*] sep $sep] @rest , $($rest)*);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)*] @kind [] @rest [$($tt:tt)*] $($rest:tt)*) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [[$($tt)*]] @rest $($rest)*);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)*] @kind [] @rest ($($tt:tt)*) $($rest:tt)*) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [($($tt)*)] @rest $($rest)*);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)*] @kind [] @rest _ $($rest:tt)*) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [_] @rest $($rest)*);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)*] @kind [] @rest $ty:ty, $($rest:tt)*) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [$ty] @rest , $($rest)*);
+    };
+    (@buf [$buf:ident] @value [$($value:tt)*] @kind [] @rest $ty:ty) => {
+        $crate::output!(@buf [$buf] @value [$($value)*] @kind [$ty] @rest);
+    };
+
+    // write a line
+    (@buf [$buf:ident] @value [$($value:tt)*] @kind [$($kind:tt)+] @rest $(, $($rest:tt)*)?) => {
+        $crate::output!(@write [$buf] @value [$($value)*] @kind [$($kind)*]);
+        $buf.push(b'\n');
+        $crate::output!(@buf [$buf] @rest $($($rest)*)?);
+    };
+
+    // write array
+    (@write [$buf:ident] @value [$($value:tt)*] @kind [[$($kind:tt)*] $(sep $sep:expr)?]) => {
+        $crate::output!(
+            @array [$buf] @value [$($value)*] @sep [$($sep)?] @kind [] @rest $($kind)*
+        )
+    };
+    (@array [$buf:ident] @value [$($value:tt)*] @sep [$($sep:expr)?] @kind [$($kind:tt)*] @rest) => {{
+        let __value = &($($value)*);
+        <usize as $crate::output::Writable>::write(&__value.len(), &mut $buf);
+        $buf.push(b'\n');
+        $crate::output!(@array [$buf] @value [__value] @sep [$($sep)?] @kind [$($kind)*] @len []);
+    }};
+    (@array [$buf:ident] @value [$($value:tt)*] @sep [$($sep:expr)?] @kind [$($kind:tt)*] @rest ; $($len:tt)*) => {{
+        let __value = &($($value)*);
+        let __len = $($len)*;
+        assert_eq!(
+            __value.len(),
+            __len,
+            "the number of elements to write differs from the specified length",
+        );
+        $crate::output!(@array [$buf] @value [__value] @sep [$($sep)?] @kind [$($kind)*] @len []);
+    }};
+    (@array [$buf:ident] @value [$($value:tt)*] @sep [$($sep:expr)?] @kind [$($kind:tt)*] @rest $tt:tt $($rest:tt)*) => {
+        $crate::output!(
+            @array [$buf] @value [$($value)*] @sep [$($sep)?] @kind [$($kind)* $tt] @rest $($rest)*
+        )
+    };
+    (@array [$buf:ident] @value [$value:ident] @sep [$($sep:expr)?] @kind [$($kind:tt)*] @len []) => {
+        for (__i, __elem) in $value.iter().enumerate() {
+            if __i != 0 {
+                $crate::output!(@sep [$buf] @value [__elem] @sep [$($sep)?] @kind [$($kind)*]);
+            }
+            $crate::output!(@write [$buf] @value [__elem] @kind [$($kind)*]);
+        }
+    };
+
+    // separator between the elements of an array
+    (@sep [$buf:ident] @value [$value:ident] @sep [$sep:expr] @kind [$($kind:tt)*]) => {{
+        use $crate::output::__Separator as _;
+        $sep.__proconio_write_sep(&mut $buf);
+    }};
+    (@sep [$buf:ident] @value [$value:ident] @sep [] @kind [_]) => {{
+        use $crate::output::__WriteValue as _;
+        $buf.extend_from_slice($value.__proconio_sep().as_bytes());
+    }};
+    (@sep [$buf:ident] @value [$value:ident] @sep [] @kind [[$($kind:tt)*] $($rest:tt)*]) => {
+        $buf.push(b'\n');
+    };
+    (@sep [$buf:ident] @value [$value:ident] @sep [] @kind [($($kind:tt)*)]) => {
+        $buf.push(b'\n');
+    };
+    (@sep [$buf:ident] @value [$value:ident] @sep [] @kind [$kind:ty]) => {
+        $buf.extend_from_slice(<$kind as $crate::output::Writable>::SEP.as_bytes());
+    };
+
+    // write tuple
+    (@write [$buf:ident] @value [$($value:tt)*] @kind [($($kinds:tt)*)]) => {
+        $crate::output!(@tuple [$buf] @value [$($value)*] @elems [] @current [] @rest $($kinds)*)
+    };
+    (@tuple [$buf:ident] @value [$($value:tt)*] @elems [$($elems:tt)*] @current [] @rest) => {
+        $crate::output!(@tuple [$buf] @value [$($value)*] @elems [$($elems)*])
+    };
+    (@tuple [$buf:ident] @value [$($value:tt)*] @elems [$($elems:tt)*] @current [$($curr:tt)+] @rest) => {
+        $crate::output!(@tuple [$buf] @value [$($value)*] @elems [$($elems)* [__elem $($curr)*]] @current [] @rest)
+    };
+    (@tuple [$buf:ident] @value [$($value:tt)*] @elems [$($elems:tt)*] @current [$($curr:tt)+] @rest , $($rest:tt)*) => {
+        $crate::output!(@tuple [$buf] @value [$($value)*] @elems [$($elems)* [__elem $($curr)*]] @current [] @rest $($rest)*)
+    };
+    (@tuple [$buf:ident] @value [$($value:tt)*] @elems [$($elems:tt)*] @current [$($curr:tt)*] @rest $tt:tt $($rest:tt)*) => {
+        $crate::output!(@tuple [$buf] @value [$($value)*] @elems [$($elems)*] @current [$($curr)* $tt] @rest $($rest)*)
+    };
+    (@tuple [$buf:ident] @value [$($value:tt)*] @elems [[$first:ident $($first_kind:tt)*] $([$elem:ident $($kind:tt)*])*]) => {
+        // `__elem` is introduced in a different expansion for each element, so the names don't
+        // collide thanks to the hygiene.
+        match &($($value)*) {
+            ($first, $($elem,)*) => {
+                $crate::output!(@write [$buf] @value [$first] @kind [$($first_kind)*]);
+                $(
+                    $buf.push(b' ');
+                    $crate::output!(@write [$buf] @value [$elem] @kind [$($kind)*]);
+                )*
+            }
+        }
+    };
+
+    // write other
+    (@write [$buf:ident] @value [$($value:tt)*] @kind [_]) => {{
+        use $crate::output::__WriteValue as _;
+        (&($($value)*)).__proconio_write(&mut $buf);
+    }};
+    (@write [$buf:ident] @value [$($value:tt)*] @kind [$kind:ty]) => {
+        $crate::output::__write::<$kind, _>(&($($value)*), &mut $buf)
+    };
+
+    (@$($tt:tt)*) => {
+        compile_error!(concat!(
+            "Reached unreachable statement while parsing macro input.  ",
+            "This is a bug in `proconio`.  ",
+            "Please report this issue from ",
+            "<https://github.com/statiolake/proconio-rs/issues>."
+        ));
+    };
+
+    // interface
+    (to $writer:expr, $($rest:tt)*) => {{
+        let mut __writer = $writer;
+        let mut __buf = ::std::vec::Vec::<u8>::new();
+        $crate::output!(@buf [__buf] @rest $($rest)*);
+        ::std::io::Write::write_all(&mut __writer, &__buf).unwrap();
+    }};
+    ($($rest:tt)*) => {{
+        let mut __buf = ::std::vec::Vec::<u8>::new();
+        $crate::output!(@buf [__buf] @rest $($rest)*);
+        ::std::io::Write::write_all(&mut $crate::out::stdout(), &__buf).unwrap();
+    }};
+}
@@ -54,9 +54,17 @@
 //! point of the read result, but, unintentionally, it may differ in a bare possibility. If it
 //! should differ, you can manually specify the source of `input!`.
 use std::any::type_name;
+use std::cmp::Reverse;
 use std::fmt::Debug;
 use std::io::BufRead;
+use std::marker::PhantomData;
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Saturating, Wrapping,
+};
+use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::Arc;
 
 pub mod line;
 pub mod once;
@@ -115,31 +123,305 @@ pub trait Readable {
     fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Self::Output;
 }
 
-// implementations of Readable for any `FromStr` types including primitives.
-impl<T: FromStr> Readable for T
+/// A trait for types parsed from a single token.
+///
+/// Every implementor is also `Readable`, reading one token.  Unlike `FromStr`, it panics on the
+/// invalid token, so that `proconio` can use the specialized parsers like the fast integer parser.
+/// It is implemented for the primitives, `String`, the `NonZero*` integers and `Box<T>`.
+///
+/// # Migration from `FromStr`
+///
+/// Every type implementing `FromStr` used to be `Readable` through a blanket implementation,
+/// which made it impossible to implement `Readable` for a type implementing `FromStr`.  The
+/// blanket implementation is now for `FromToken` instead.  `input!` still reads any `FromStr`
+/// type by itself, but such a type is no longer `Readable`, which is required for a member of
+/// `#[derive_readable]`, the inner type of `Reverse<T>` and so on.  To make it `Readable` again:
+///
+/// - implement `FromToken` for your own type, as below.
+/// - read a type from another crate, like a big integer, by [`Parse<T>`](crate::marker::Parse).
+/// - or implement `Readable` directly, if it is read from more than one token.
+///
+/// ```
+/// # extern crate proconio;
+/// use proconio::input;
+/// use proconio::source::auto::AutoSource;
+/// use proconio::source::FromToken;
+/// use std::cmp::Reverse;
+///
+/// #[derive(PartialEq, Debug)]
+/// struct Even(u32);
+///
+/// impl FromToken for Even {
+///     fn from_token(token: &str) -> Even {
+///         let value = u32::from_token(token);
+///         assert!(value % 2 == 0, "{} is not even", value);
+///         Even(value)
+///     }
+/// }
+///
+/// let source = AutoSource::from("42 8");
+/// input! {
+///     from source,
+///     even: Even,
+///     reversed: Reverse<Even>,
+/// }
+///
+/// assert_eq!(even, Even(42));
+/// assert_eq!(reversed, Reverse(Even(8)));
+/// ```
+pub trait FromToken: Sized {
+    /// Parses the token, panicking if it is invalid.
+    fn from_token(token: &str) -> Self;
+}
+
+/// Parses a token by `FromStr`, panicking with a helpful message on failure.
+#[doc(hidden)]
+pub fn parse_str<T: FromStr>(token: &str) -> T
+where
+    T::Err: Debug,
+{
+    match token.parse() {
+        Ok(v) => v,
+        Err(e) => panic!(
+            concat!(
+                "failed to parse the input `{input}` ",
+                "to the value of type `{ty}`: {err:?}; ",
+                "ensure that the input format is collectly specified ",
+                "and that the input value must handle specified type.",
+            ),
+            input = token,
+            ty = type_name::<T>(),
+            err = e,
+        ),
+    }
+}
+
+/// Reads a token and parses it by `FromStr`, panicking with a helpful message on failure.
+#[doc(hidden)]
+pub fn parse_token<T: FromStr, R: BufRead, S: Source<R>>(source: &mut S) -> T
 where
     T::Err: Debug,
 {
+    parse_str(source.next_token_unwrap())
+}
+
+// Autoref specialization used by `read_value!` to read a type by `Readable` if available, and by
+// `FromStr` otherwise, so that `input!` keeps reading any `FromStr` type like `Ipv4Addr` or a
+// big integer from another crate.  `(&&Kind::<T>(PhantomData)).__proconio_read(source)` finds
+// `ViaReadable` first since its receiver type `&&Kind` matches without auto-deref.
+
+#[doc(hidden)]
+pub struct Kind<T>(pub PhantomData<fn() -> T>);
+
+#[doc(hidden)]
+pub trait ViaReadable {
+    type Output;
+    fn __proconio_read<R: BufRead, S: Source<R>>(&self, source: &mut S) -> Self::Output;
+}
+
+impl<T: Readable> ViaReadable for &Kind<T> {
+    type Output = T::Output;
+    fn __proconio_read<R: BufRead, S: Source<R>>(&self, source: &mut S) -> T::Output {
+        T::read(source)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaFromStr {
+    type Output;
+    fn __proconio_read<R: BufRead, S: Source<R>>(&self, source: &mut S) -> Self::Output;
+}
+
+impl<T: FromStr> ViaFromStr for Kind<T>
+where
+    T::Err: Debug,
+{
+    type Output = T;
+    fn __proconio_read<R: BufRead, S: Source<R>>(&self, source: &mut S) -> T {
+        parse_token(source)
+    }
+}
+
+// The integers are parsed digit by digit, which is faster than `FromStr`.  The invalid or
+// overflowing tokens are passed to `FromStr` for the error message.
+macro_rules! impl_from_token_for_unsigned {
+    ($($t:ty),* $(,)?) => {$(
+        impl FromToken for $t {
+            fn from_token(token: &str) -> $t {
+                let digits = token.strip_prefix('+').unwrap_or(token).as_bytes();
+                if digits.is_empty() {
+                    return parse_str(token);
+                }
+
+                let mut value: $t = 0;
+                for &b in digits {
+                    let digit = b.wrapping_sub(b'0');
+                    let next = if digit < 10 {
+                        value.checked_mul(10).and_then(|v| v.checked_add(digit as $t))
+                    } else {
+                        None
+                    };
+                    match next {
+                        Some(next) => value = next,
+                        None => return parse_str(token),
+                    }
+                }
+                value
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_from_token_for_signed {
+    ($($t:ty),* $(,)?) => {$(
+        impl FromToken for $t {
+            fn from_token(token: &str) -> $t {
+                let (negative, digits) = match token.as_bytes() {
+                    [b'-', digits @ ..] => (true, digits),
+                    [b'+', digits @ ..] => (false, digits),
+                    digits => (false, digits),
+                };
+                if digits.is_empty() {
+                    return parse_str(token);
+                }
+
+                // accumulate towards the sign so that `MIN` doesn't overflow
+                let mut value: $t = 0;
+                for &b in digits {
+                    let digit = b.wrapping_sub(b'0');
+                    let next = if digit >= 10 {
+                        None
+                    } else if negative {
+                        value.checked_mul(10).and_then(|v| v.checked_sub(digit as $t))
+                    } else {
+                        value.checked_mul(10).and_then(|v| v.checked_add(digit as $t))
+                    };
+                    match next {
+                        Some(next) => value = next,
+                        None => return parse_str(token),
+                    }
+                }
+                value
+            }
+        }
+    )*};
+}
+
+impl_from_token_for_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_token_for_signed!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_from_token_via_from_str {
+    ($($t:ty),* $(,)?) => {$(
+        impl FromToken for $t {
+            fn from_token(token: &str) -> $t {
+                parse_str(token)
+            }
+        }
+    )*};
+}
+
+impl_from_token_via_from_str!(f32, f64, bool, char, String);
+
+// Every `FromToken` type is `Readable`, reading one token.  This is the opt-in for your own types
+// which were read through the `FromStr` blanket implementation.
+impl<T: FromToken> Readable for T {
     type Output = T;
     fn read<R: BufRead, S: Source<R>>(source: &mut S) -> T {
-        let token = source.next_token_unwrap();
-        match token.parse() {
-            Ok(v) => v,
-            Err(e) => panic!(
-                concat!(
-                    "failed to parse the input `{input}` ",
-                    "to the value of type `{ty}`: {err:?}; ",
-                    "ensure that the input format is collectly specified ",
-                    "and that the input value must handle specified type.",
-                ),
-                input = token,
-                ty = type_name::<T>(),
-                err = e,
-            ),
+        T::from_token(source.next_token_unwrap())
+    }
+}
+
+// implementations of Readable for tuples, reading each element in order.
+macro_rules! impl_readable_for_tuple {
+    ($($t:ident)*) => {
+        impl<$($t: Readable),*> Readable for ($($t,)*) {
+            type Output = ($($t::Output,)*);
+            fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Self::Output {
+                ($($t::read(source),)*)
+            }
+        }
+    };
+}
+
+impl_readable_for_tuple!(T1);
+impl_readable_for_tuple!(T1 T2);
+impl_readable_for_tuple!(T1 T2 T3);
+impl_readable_for_tuple!(T1 T2 T3 T4);
+impl_readable_for_tuple!(T1 T2 T3 T4 T5);
+impl_readable_for_tuple!(T1 T2 T3 T4 T5 T6);
+impl_readable_for_tuple!(T1 T2 T3 T4 T5 T6 T7);
+impl_readable_for_tuple!(T1 T2 T3 T4 T5 T6 T7 T8);
+impl_readable_for_tuple!(T1 T2 T3 T4 T5 T6 T7 T8 T9);
+impl_readable_for_tuple!(T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+impl_readable_for_tuple!(T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+impl_readable_for_tuple!(T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12);
+
+// implementation of Readable for fixed-size arrays, reading `N` elements in order.
+impl<T: Readable, const N: usize> Readable for [T; N] {
+    type Output = [T::Output; N];
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> [T::Output; N] {
+        std::array::from_fn(|_| T::read(source))
+    }
+}
+
+// implementations of Readable for the std wrappers, wrapping the value read by the inner type.
+macro_rules! impl_readable_for_wrapper {
+    ($($wrapper:ident => $new:expr),* $(,)?) => {$(
+        impl<T: Readable> Readable for $wrapper<T> {
+            type Output = $wrapper<T::Output>;
+            fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Self::Output {
+                $new(T::read(source))
+            }
         }
+    )*};
+}
+
+impl_readable_for_wrapper!(
+    Reverse => Reverse,
+    Wrapping => Wrapping,
+    Saturating => Saturating,
+    Rc => Rc::new,
+    Arc => Arc::new,
+);
+
+// `Box<T>` is a fundamental type, so `Readable` for `Box<T>` of every `Readable` `T` would
+// conflict with the implementation for `FromToken`.  Only the boxed tokens are supported.
+/// Reads the inner single token.  `Box<T>` of the other `Readable` types, like `Box<(i32, i32)>`
+/// or `Box<Usize1>`, is not `Readable`.
+impl<T: FromToken> FromToken for Box<T> {
+    fn from_token(token: &str) -> Box<T> {
+        Box::new(T::from_token(token))
     }
 }
 
+// implementations of FromToken for the non-zero integers, which panic on zero.
+macro_rules! impl_from_token_for_non_zero {
+    ($($t:ident($inner:ty)),* $(,)?) => {$(
+        impl FromToken for $t {
+            fn from_token(token: &str) -> $t {
+                $t::new(<$inner>::from_token(token)).unwrap_or_else(|| {
+                    panic!(concat!("attempted to read the value 0 as a ", stringify!($t)))
+                })
+            }
+        }
+    )*};
+}
+
+impl_from_token_for_non_zero!(
+    NonZeroI8(i8),
+    NonZeroI16(i16),
+    NonZeroI32(i32),
+    NonZeroI64(i64),
+    NonZeroI128(i128),
+    NonZeroIsize(isize),
+    NonZeroU8(u8),
+    NonZeroU16(u16),
+    NonZeroU32(u32),
+    NonZeroU64(u64),
+    NonZeroU128(u128),
+    NonZeroUsize(usize),
+);
+
 /// A trait that specifies how to read a value of some type from `Source` in such a way that is only
 /// known at runtime, such as reading a vector or graph whose size is specified at runtime.
 ///
@@ -5,9 +5,14 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
 // distributed except according to those terms.
 
-use proconio::derive_readable;
 use proconio::input;
+use proconio::marker::{Chars, LenPrefixed, Usize1};
+use proconio::output;
 use proconio::source::auto::AutoSource;
+use proconio::source::{Readable, RuntimeReadable, Source};
+use proconio::{derive_readable, derive_runtime_readable, derive_writable, Readable};
+use std::io::BufRead;
+use std::marker::PhantomData;
 
 #[derive_readable]
 #[derive(PartialEq, Debug)]
@@ -39,3 +44,607 @@ fn derive() {
     assert_eq!(edge.weight, Weight);
     assert_eq!(edge.cost, Cost(35));
 }
+
+#[derive_readable]
+#[derive_writable]
+#[derive(PartialEq, Debug)]
+struct Tag;
+
+#[derive_writable]
+#[derive_readable]
+#[derive(PartialEq, Debug)]
+struct Score(pub(crate) i32);
+
+#[derive_readable]
+#[derive_writable]
+#[derive(PartialEq, Debug)]
+struct Query {
+    target: Usize1,
+    tag: Tag,
+    name: Chars,
+}
+
+#[derive_writable]
+#[derive_readable]
+#[derive(PartialEq, Debug)]
+struct Case {
+    score: Score,
+    first: Query,
+    second: Query,
+}
+
+#[test]
+fn derive_writable() {
+    let case = Case {
+        score: Score(-3),
+        first: Query {
+            target: 0,
+            tag: Tag,
+            name: "abc".chars().collect(),
+        },
+        second: Query {
+            target: 9,
+            tag: Tag,
+            name: vec!['x'],
+        },
+    };
+
+    let mut buf = Vec::new();
+    output! {
+        to &mut buf,
+        &case: Case,
+        [Score(1), Score(2)]: [Score; 2],
+    }
+    let written = String::from_utf8(buf).unwrap();
+    assert_eq!(written, "-3\n1\nabc\n10\nx\n1 2\n");
+
+    let source = AutoSource::from(&*written);
+    input! {
+        from source,
+        read: Case,
+        scores: [Score; 2],
+    }
+    assert_eq!(read, case);
+    assert_eq!(scores, [Score(1), Score(2)]);
+}
+
+#[derive_readable]
+#[derive(PartialEq, Debug)]
+enum Command {
+    Add(Usize1, i64),
+    Remove { index: Usize1 },
+    Print,
+}
+
+#[derive_readable]
+#[derive(PartialEq, Debug)]
+enum Named {
+    #[tag = "push"]
+    Push(Chars),
+    #[tag = "pop"]
+    Pop,
+    #[tag = 0]
+    Clear,
+}
+
+#[test]
+fn derive_enum() {
+    let source = AutoSource::from("4\n1 3 -5\n2 1\n3\n1 1 7\npush ab pop 0");
+    input! {
+        from source,
+        n: usize,
+        commands: [Command; n],
+        named: [Named; 3],
+    }
+
+    assert_eq!(
+        commands,
+        [
+            Command::Add(2, -5),
+            Command::Remove { index: 0 },
+            Command::Print,
+            Command::Add(0, 7),
+        ]
+    );
+    assert_eq!(
+        named,
+        [Named::Push(vec!['a', 'b']), Named::Pop, Named::Clear]
+    );
+}
+
+#[test]
+#[should_panic(expected = "unknown tag `4` for `Command`; expected `1`, `2` or `3`.")]
+fn derive_enum_unknown_tag() {
+    let source = AutoSource::from("4 1");
+    input! {
+        from source,
+        _command: Command,
+    }
+}
+
+#[derive_readable]
+#[derive_writable]
+#[derive(PartialEq, Debug)]
+struct Pair<T> {
+    a: T,
+    b: T,
+}
+
+#[derive_readable]
+#[derive_writable]
+#[derive(PartialEq, Debug)]
+struct WeightedEdge<W: Copy, R = i64>
+where
+    W: PartialOrd,
+{
+    from: Usize1,
+    to: Usize1,
+    weight: W,
+    rest: Score,
+    _extra: PhantomReadable<R>,
+}
+
+#[derive_readable]
+#[derive(PartialEq, Debug)]
+struct Bounded<'a, T, const K: usize>(T, Borrowed<'a>);
+
+impl<'a, T: Readable, const K: usize> Bounded<'a, T, K> {
+    fn bound(&self) -> usize {
+        K
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Borrowed<'a>(PhantomData<&'a ()>);
+
+impl<'a> Readable for Borrowed<'a> {
+    type Output = Self;
+    fn read<R: BufRead, S: Source<R>>(_source: &mut S) -> Self {
+        Borrowed(PhantomData)
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct PhantomReadable<T>(PhantomData<T>);
+
+impl<T> Readable for PhantomReadable<T> {
+    type Output = Self;
+    fn read<R: BufRead, S: Source<R>>(_source: &mut S) -> Self {
+        PhantomReadable(PhantomData)
+    }
+}
+
+impl<T> proconio::output::Writable for PhantomReadable<T> {
+    type Value = Self;
+    const SEP: &'static str = "";
+    fn write(_value: &Self, _buf: &mut Vec<u8>) {}
+}
+
+#[test]
+fn derive_generics() {
+    let source = AutoSource::from("1 2 ab cd\n1 3 2.5 4\n7");
+    input! {
+        from source,
+        pair: Pair<Usize1>,
+        chars: Pair<Chars>,
+        edge: WeightedEdge<f64>,
+        bounded: Bounded<i32, 5>,
+    }
+
+    // `#[derive(PartialEq)]` requires the markers themselves to be `PartialEq`.
+    assert_eq!((pair.a, pair.b), (0, 1));
+    assert_eq!((&*chars.a, &*chars.b), (&['a', 'b'][..], &['c', 'd'][..]));
+    assert_eq!(
+        edge,
+        WeightedEdge {
+            from: 0,
+            to: 2,
+            weight: 2.5,
+            rest: Score(4),
+            _extra: PhantomReadable(PhantomData),
+        }
+    );
+    assert_eq!(bounded.0, 7);
+    assert_eq!(bounded.bound(), 5);
+
+    let mut buf = Vec::new();
+    output! {
+        to &mut buf,
+        pair: Pair<Usize1>,
+        chars: Pair<Chars>,
+        edge: WeightedEdge<f64>,
+    }
+    assert_eq!(buf, b"1 2\nab\ncd\n1 3 2.5 4\n");
+}
+
+struct Grid(usize, usize);
+
+impl RuntimeReadable for Grid {
+    type Output = Vec<Vec<char>>;
+    fn read<R: BufRead, S: Source<R>>(self, source: &mut S) -> Self::Output {
+        let Grid(h, w) = self;
+        input! {
+            from source,
+            grid: [Chars; h],
+        }
+        assert!(grid.iter().all(|row| row.len() == w));
+        grid
+    }
+}
+
+#[derive_readable]
+#[derive(PartialEq, Debug)]
+struct Problem {
+    n: usize,
+    #[readable(len = n)]
+    a: Vec<Usize1>,
+    m: usize,
+    #[readable(kind = "[(Usize1, i64); m]")]
+    edges: Vec<(usize, i64)>,
+    h: usize,
+    w: usize,
+    #[readable(with = Grid(h, w))]
+    grid: Vec<Vec<char>>,
+    #[readable(skip)]
+    visited: Vec<bool>,
+    #[readable(default = n * 2)]
+    doubled: usize,
+}
+
+#[test]
+fn derive_field_attrs() {
+    let source = AutoSource::from("3\n1 2 3\n2\n1 5\n3 -1\n2 2\n#.\n.#\n");
+    input! {
+        from source,
+        problem: Problem,
+    }
+
+    assert_eq!(
+        problem,
+        Problem {
+            n: 3,
+            a: vec![0, 1, 2],
+            m: 2,
+            edges: vec![(0, 5), (2, -1)],
+            h: 2,
+            w: 2,
+            grid: vec![vec!['#', '.'], vec!['.', '#']],
+            visited: vec![],
+            doubled: 6,
+        }
+    );
+}
+
+#[derive_writable]
+#[derive_readable]
+#[derive(PartialEq, Debug)]
+struct Permutation {
+    n: usize,
+    #[readable(len = n)]
+    p: Vec<Usize1>,
+    #[readable(skip)]
+    inverse: Vec<usize>,
+}
+
+#[test]
+fn derive_field_attrs_round_trip() {
+    let permutation = Permutation {
+        n: 3,
+        p: vec![2, 0, 1],
+        inverse: vec![1, 2, 0],
+    };
+
+    let mut buf = Vec::new();
+    output! {
+        to &mut buf,
+        &permutation: Permutation,
+    }
+    assert_eq!(buf, b"3\n3 1 2\n");
+
+    let source = AutoSource::from(std::str::from_utf8(&buf).unwrap());
+    input! {
+        from source,
+        read: Permutation,
+    }
+    assert_eq!((read.n, read.p), (3, vec![2, 0, 1]));
+    assert!(read.inverse.is_empty());
+}
+
+#[derive_readable]
+#[derive_writable]
+#[derive(PartialEq, Debug)]
+struct Sequence {
+    len: usize,
+    #[readable(len = len)]
+    values: Vec<Usize1>,
+    #[readable(default = len + 1)]
+    next: usize,
+}
+
+#[test]
+fn derive_field_attrs_round_trip_readable_first() {
+    let sequence = Sequence {
+        len: 2,
+        values: vec![4, 0],
+        next: 3,
+    };
+
+    let mut buf = Vec::new();
+    output! {
+        to &mut buf,
+        &sequence: Sequence,
+    }
+    assert_eq!(buf, b"2\n5 1\n");
+
+    let source = AutoSource::from(std::str::from_utf8(&buf).unwrap());
+    input! {
+        from source,
+        read: Sequence,
+    }
+    assert_eq!(read, sequence);
+}
+
+#[derive(Readable, PartialEq, Debug)]
+struct Road {
+    #[readable(as = Usize1)]
+    from: usize,
+    #[readable(as = Usize1)]
+    to: usize,
+    cost: i64,
+}
+
+#[derive(Readable, PartialEq, Debug)]
+struct Map<T> {
+    n: usize,
+    #[readable(as = Chars)]
+    name: Vec<char>,
+    #[readable(len = n)]
+    roads: Vec<Road>,
+    #[readable(len = n, as = Usize1)]
+    ids: Vec<usize>,
+    value: T,
+    #[readable(skip)]
+    cache: Vec<usize>,
+}
+
+#[derive(Readable, PartialEq, Debug)]
+enum Event {
+    #[tag = "go"]
+    Go(Road),
+    Stop,
+}
+
+#[test]
+fn derive_macro() {
+    let source = AutoSource::from("2 map\n1 2 5\n2 1 -5\n2 1\n3.5\ngo 1 1 0 2");
+    input! {
+        from source,
+        map: Map<f64>,
+        events: [Event; 2],
+    }
+
+    assert_eq!(
+        map,
+        Map {
+            n: 2,
+            name: vec!['m', 'a', 'p'],
+            roads: vec![
+                Road {
+                    from: 0,
+                    to: 1,
+                    cost: 5,
+                },
+                Road {
+                    from: 1,
+                    to: 0,
+                    cost: -5,
+                },
+            ],
+            ids: vec![1, 0],
+            value: 3.5,
+            cache: vec![],
+        }
+    );
+    assert_eq!(
+        events,
+        [
+            Event::Go(Road {
+                from: 0,
+                to: 0,
+                cost: 0,
+            }),
+            Event::Stop,
+        ]
+    );
+}
+
+#[derive_readable]
+#[derive_writable]
+#[derive(PartialEq, Debug)]
+struct Shifted {
+    #[readable(as = Usize1)]
+    index: usize,
+    #[readable(len = 2, as = Usize1)]
+    pair: Vec<usize>,
+}
+
+#[test]
+fn derive_readable_as_round_trip() {
+    let shifted = Shifted {
+        index: 0,
+        pair: vec![1, 2],
+    };
+
+    let mut buf = Vec::new();
+    output! {
+        to &mut buf,
+        &shifted: Shifted,
+    }
+    assert_eq!(buf, b"1\n2 3\n");
+
+    let source = AutoSource::from(std::str::from_utf8(&buf).unwrap());
+    input! {
+        from source,
+        read: Shifted,
+    }
+    assert_eq!(read, shifted);
+}
+
+#[derive_readable]
+#[derive_writable]
+#[derive(PartialEq, Debug)]
+struct Roads {
+    m: usize,
+    #[readable(kind = "[(Usize1, Usize1); m]")]
+    edges: Vec<(usize, usize)>,
+    #[readable(kind = "[i64]")]
+    costs: Vec<i64>,
+}
+
+#[test]
+fn derive_readable_kind_round_trip() {
+    let source = AutoSource::from("2\n1 2\n2 3\n2\n5 7\n");
+    input! {
+        from source,
+        roads: Roads,
+    }
+    assert_eq!(
+        roads,
+        Roads {
+            m: 2,
+            edges: vec![(0, 1), (1, 2)],
+            costs: vec![5, 7],
+        }
+    );
+
+    let mut buf = Vec::new();
+    output! {
+        to &mut buf,
+        roads: Roads,
+    }
+    assert_eq!(buf, b"2\n1 2\n2 3\n2\n5 7\n");
+}
+
+#[derive_writable]
+struct Layout {
+    n: usize,
+    m: usize,
+    single: Vec<i32>,
+    empty: Vec<i32>,
+    last: usize,
+}
+
+#[test]
+fn derive_writable_layout_by_type() {
+    let mut buf = Vec::new();
+    output! {
+        to &mut buf,
+        Layout {
+            n: 1,
+            m: 0,
+            single: vec![5],
+            empty: vec![],
+            last: 2,
+        }: Layout,
+    }
+    assert_eq!(buf, b"1 0\n5\n\n2\n");
+}
+
+#[derive_runtime_readable(params(n: usize, m: usize))]
+#[derive(PartialEq, Debug)]
+struct Graph {
+    #[readable(kind = "[(Usize1, Usize1); m]")]
+    edges: Vec<(usize, usize)>,
+    #[readable(kind = "[i64; n]")]
+    weights: Vec<i64>,
+    #[readable(default = vec![Vec::new(); n])]
+    adjacency: Vec<Vec<usize>>,
+}
+
+#[derive_runtime_readable(name = Rows, params(h: usize))]
+#[derive(PartialEq, Debug)]
+struct Board {
+    #[readable(len = h)]
+    rows: Vec<Chars>,
+}
+
+#[test]
+fn derive_runtime_readable() {
+    let source = AutoSource::from("3 2\n1 2\n2 3\n10 20 30\n2\n#.\n.#");
+    input! {
+        from source,
+        n: usize,
+        m: usize,
+        g: with GraphSpec { n, m },
+        h: usize,
+        board: with Rows { h },
+    }
+
+    assert_eq!(
+        g,
+        Graph {
+            edges: vec![(0, 1), (1, 2)],
+            weights: vec![10, 20, 30],
+            adjacency: vec![vec![]; 3],
+        }
+    );
+    assert_eq!(board.rows, [vec!['#', '.'], vec!['.', '#']],);
+}
+
+#[derive_readable]
+#[derive(PartialEq, Debug)]
+struct Segment {
+    ends: (Usize1, Usize1),
+    color: [u8; 3],
+    #[readable(len = 2)]
+    marks: Vec<(Usize1, i64)>,
+}
+
+#[test]
+fn derive_tuple_and_array_fields() {
+    let source = AutoSource::from("1 4\n255 128 0\n2 -5\n3 7");
+    input! {
+        from source,
+        segment: Segment,
+    }
+
+    assert_eq!(
+        segment,
+        Segment {
+            ends: (0, 3),
+            color: [255, 128, 0],
+            marks: vec![(1, -5), (2, 7)],
+        }
+    );
+}
+
+#[derive_readable]
+#[derive_writable]
+#[derive(PartialEq, Debug)]
+struct Tree {
+    root: Usize1,
+    edges: LenPrefixed<(Usize1, Usize1), Usize1>,
+}
+
+#[test]
+fn derive_len_prefixed_round_trip() {
+    let source = AutoSource::from("2\n3\n1 2\n2 3\n");
+    input! {
+        from source,
+        tree: Tree,
+    }
+    assert_eq!(
+        tree,
+        Tree {
+            root: 1,
+            edges: vec![(0, 1), (1, 2)],
+        }
+    );
+
+    let mut buf = Vec::new();
+    output! {
+        to &mut buf,
+        tree: Tree,
+    }
+    assert_eq!(buf, b"2\n3\n1 2\n2 3\n");
+}
@@ -18,10 +18,13 @@ fn foo() -> i32 {
 fn main() {
     let clo = || "AtCoder"; // OK;
     let name = clo();
-    // let _err = || println!("hello"); // error even it's not spown
-    // std::thread::spawn(|| {
-    //     std::println!("hello");
-    // }); // of course error
+    let hello = || println!("hello"); // OK: print macros can be used in closures
+    hello();
+    std::thread::spawn(|| {
+        println!("hello");
+    })
+    .join()
+    .unwrap(); // OK: closures with print macros are `Send`
     println!();
     println!("hello, world, {}!", name);
     println!("{}", foo());
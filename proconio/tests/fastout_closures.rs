@@ -0,0 +1,78 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use proconio::fastout;
+
+fn helper() {
+    // must be kept in order with the output of `#[fastout]` function.
+    proconio::out::println!("helper");
+}
+
+#[fastout]
+fn greet(name: &str) {
+    println!("hello, {}", name);
+}
+
+#[fastout]
+fn test_closures(early_return: bool) {
+    println!("start");
+    helper();
+    (1..=3).for_each(|x| print!("{} ", x));
+    println!();
+
+    for i in 0..2 {
+        // a #[fastout] function called in another thread must not deadlock.
+        let thread = std::thread::spawn(move || greet(&format!("thread {}", i)));
+        thread.join().unwrap();
+    }
+
+    let thread = std::thread::spawn(|| {
+        for i in 0..3 {
+            println!("spawned {}", i);
+        }
+    });
+    thread.join().unwrap();
+
+    if early_return {
+        // early return must flush the buffer as well.
+        println!("early return");
+        return;
+    }
+    println!("end");
+}
+
+fn test_for(mode: &str, expected_stdout: &str) {
+    use assert_cli::Assert;
+    use std::env::args;
+    Assert::command(&[&*args().next().unwrap(), mode])
+        .stdout()
+        .is(expected_stdout)
+        .and()
+        .stderr()
+        .is("")
+        .unwrap();
+}
+
+fn main() {
+    use std::env::args;
+
+    // relaunch the app to capture standard output
+    match args().nth(1).as_deref() {
+        None => {
+            let expected = concat!(
+                "start\nhelper\n1 2 3 \n",
+                "hello, thread 0\nhello, thread 1\n",
+                "spawned 0\nspawned 1\nspawned 2\n",
+            );
+            test_for("normal", &format!("{}end\n", expected));
+            test_for("early", &format!("{}early return\n", expected));
+        }
+        Some("normal") => test_closures(false),
+        Some("early") => test_closures(true),
+        Some(mode) => panic!("unknown mode: {}", mode),
+    }
+}
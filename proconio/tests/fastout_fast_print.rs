@@ -0,0 +1,72 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use proconio::fastout;
+use proconio::out::fixed;
+use std::fmt;
+
+struct Point(i32, i32);
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.0, self.1)
+    }
+}
+
+struct Logged(i32);
+
+impl fmt::Display for Logged {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        proconio::out::print!("formatting ");
+        write!(f, "{}", self.0)
+    }
+}
+
+fn side_effect() -> i32 {
+    proconio::out::print!("evaluated ");
+    42
+}
+
+#[fastout]
+fn fast_print() {
+    let v = [1u64, 22, 333];
+    println!("{}", v[0]);
+    println!("{} {} {}", v[1], -7i64, v.len());
+    print!("{} ", "str");
+    println!("{}", 'c');
+    println!("{} {}", Point(1, -2), 0.5);
+    println!("{} {}", fixed(2.0 / 3.0, 5), String::from("string"));
+    // arguments printing something must not deadlock.
+    println!("{}", side_effect());
+    // so does `Display` printing something.
+    println!("{}", Logged(7));
+    (0..3).for_each(|i| print!("{} ", i * i));
+    println!();
+    // format strings other than `{}`s fall back to `write!`.
+    println!("{:>3}|{x}", 1, x = 2);
+}
+
+fn main() {
+    use assert_cli::Assert;
+    use std::env::args;
+
+    // relaunch the app to capture standard output
+    match args().nth(1).as_deref() {
+        None => {
+            Assert::command(&[&*args().next().unwrap(), "fast_print"])
+                .stdout()
+                .is(concat!(
+                    "1\n22 -7 3\nstr c\n(1, -2) 0.5\n0.66667 string\nevaluated 42\nformatting 7\n",
+                    "0 1 4 \n",
+                    "  1|2\n",
+                ))
+                .unwrap();
+        }
+        Some("fast_print") => fast_print(),
+        Some(mode) => panic!("unknown mode: {}", mode),
+    }
+}
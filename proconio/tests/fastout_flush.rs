@@ -0,0 +1,114 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use proconio::fastout;
+use std::fs::File;
+use std::process::{Command, Stdio};
+
+#[fastout]
+fn question_mark() -> Result<(), std::num::ParseIntError> {
+    println!("before");
+    let _: i32 = "not a number".parse()?;
+    println!("after");
+    Ok(())
+}
+
+#[fastout]
+fn panics() {
+    println!("partial");
+    panic!("boom");
+}
+
+#[fastout(flush_on_panic)]
+fn panics_flush_on_panic() {
+    println!("partial");
+    panic!("boom");
+}
+
+#[fastout]
+fn prints() {
+    println!("hello");
+}
+
+/// Runs this test in the mode with stdout and stderr redirected to the same file, and returns
+/// whether it succeeded and the merged output.
+fn run_merged(mode: &str) -> (bool, String) {
+    let path = std::env::temp_dir().join(format!(
+        "proconio-fastout-flush-{}-{}",
+        mode,
+        std::process::id()
+    ));
+    let file = File::create(&path).unwrap();
+    let status = Command::new(std::env::args().next().unwrap())
+        .arg(mode)
+        .stdout(Stdio::from(file.try_clone().unwrap()))
+        .stderr(Stdio::from(file))
+        .status()
+        .unwrap();
+    let output = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    (status.success(), output)
+}
+
+fn assert_flushed_before_panic(mode: &str) {
+    let (success, output) = run_merged(mode);
+    assert!(!success);
+    let partial = output.find("partial").expect("partial output is lost");
+    let message = output.find("boom").expect("panic message is not printed");
+    assert!(partial < message, "unexpected order: {:?}", output);
+}
+
+fn main() {
+    use assert_cli::Assert;
+    use std::env::args;
+
+    // relaunch the app to capture standard output
+    match args().nth(1).as_deref() {
+        None => {
+            let cmd = args().next().unwrap();
+            Assert::command(&[&*cmd, "question"])
+                .stdout()
+                .is("before\n")
+                .unwrap();
+            Assert::command(&[&*cmd, "panic"])
+                .fails()
+                .and()
+                .stdout()
+                .is("partial\n")
+                .unwrap();
+
+            assert_flushed_before_panic("flush_on_panic");
+            assert_flushed_before_panic("abort");
+
+            #[cfg(target_os = "linux")]
+            {
+                let status = Command::new(&cmd)
+                    .arg("prints")
+                    .stdout(Stdio::from(File::create("/dev/full").unwrap()))
+                    .stderr(Stdio::piped())
+                    .output()
+                    .unwrap();
+                assert!(!status.status.success());
+                let stderr = String::from_utf8(status.stderr).unwrap();
+                assert!(stderr.contains("failed to flush stdout"), "{}", stderr);
+            }
+        }
+        Some("question") => assert!(question_mark().is_err()),
+        Some("panic") => panics(),
+        Some("flush_on_panic") => panics_flush_on_panic(),
+        Some("abort") => {
+            // behaves like `panic = "abort"`: the process is aborted right after the message.
+            std::panic::set_hook(Box::new(|info| {
+                eprintln!("{}", info);
+                std::process::abort();
+            }));
+            panics_flush_on_panic();
+        }
+        Some("prints") => prints(),
+        Some(mode) => panic!("unknown mode: {}", mode),
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use proconio::fastout;
+use std::fs::File;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+#[fastout]
+fn paths() {
+    println!("bare");
+    std::println!("std");
+    ::std::print!("absolute std\n");
+    writeln!(::std::io::stdout(), "::std::io::stdout").unwrap();
+    writeln!(std::io::stdout(), "std::io::stdout").unwrap();
+
+    let out = std::io::stdout();
+    let mut out = out.lock();
+    writeln!(out, "locked").unwrap();
+
+    let mut out = io::BufWriter::new(std::io::stdout().lock());
+    writeln!(out, "bufwriter").unwrap();
+    out.flush().unwrap();
+    drop(out);
+
+    // the path is rewritten even if it is not called directly.
+    std::io::stdout().flush().unwrap();
+    let stdout = std::io::stdout;
+    writeln!(stdout(), "function value").unwrap();
+}
+
+mod shadowed {
+    use proconio::fastout;
+    use std::io::Write;
+
+    mod io {
+        pub fn stdout() -> Vec<u8> {
+            Vec::new()
+        }
+    }
+
+    // `io::stdout` here is not `std::io::stdout`, so it must not be rewritten.
+    #[fastout]
+    pub fn user_io() {
+        let mut out = io::stdout();
+        writeln!(out, "not stdout").unwrap();
+        println!("{}", out.len());
+    }
+}
+
+#[fastout(debug)]
+fn debug() {
+    println!("first");
+    eprintln!("stderr");
+    let x = dbg!(1 + 1);
+    std::eprint!("std stderr\n");
+    println!("{}", x);
+}
+
+/// Runs this test in the mode with stdout and stderr redirected to the same file, and returns the
+/// merged output.
+fn run_merged(mode: &str) -> String {
+    let path = std::env::temp_dir().join(format!(
+        "proconio-fastout-intercept-{}-{}",
+        mode,
+        std::process::id()
+    ));
+    let file = File::create(&path).unwrap();
+    let status = Command::new(std::env::args().next().unwrap())
+        .arg(mode)
+        .stdout(Stdio::from(file.try_clone().unwrap()))
+        .stderr(Stdio::from(file))
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let output = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    output
+}
+
+fn main() {
+    use assert_cli::Assert;
+    use std::env::args;
+
+    // relaunch the app to capture standard output
+    match args().nth(1).as_deref() {
+        None => {
+            Assert::command(&[&*args().next().unwrap(), "paths"])
+                .stdout()
+                .is(concat!(
+                    "bare\nstd\nabsolute std\n::std::io::stdout\nstd::io::stdout\n",
+                    "locked\nbufwriter\nfunction value\n",
+                ))
+                .unwrap();
+
+            Assert::command(&[&*args().next().unwrap(), "user_io"])
+                .stdout()
+                .is("11\n")
+                .unwrap();
+
+            let output = run_merged("debug");
+            let lines: Vec<_> = output.lines().collect();
+            assert_eq!(lines.len(), 5, "{:?}", output);
+            assert_eq!(lines[0], "first");
+            assert_eq!(lines[1], "stderr");
+            assert!(lines[2].ends_with("1 + 1 = 2"), "{:?}", output);
+            assert_eq!(lines[3], "std stderr");
+            assert_eq!(lines[4], "2");
+        }
+        Some("paths") => paths(),
+        Some("user_io") => shadowed::user_io(),
+        Some("debug") => debug(),
+        Some(mode) => panic!("unknown mode: {}", mode),
+    }
+}
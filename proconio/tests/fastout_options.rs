@@ -0,0 +1,133 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use proconio::fastout;
+use std::fs::File;
+use std::process::{Command, Stdio};
+
+const OUT_FILE: &str = concat!(env!("CARGO_TARGET_TMPDIR"), "/fastout_options.txt");
+
+#[fastout(sink = stderr)]
+fn to_stderr() {
+    println!("to stderr");
+}
+
+#[fastout]
+fn nested() {
+    println!("outer");
+    // the configuration is restored when the inner function exits.
+    to_stderr();
+    println!("outer again");
+}
+
+#[fastout(sink = file(OUT_FILE))]
+fn to_file() {
+    print!("to ");
+    println!("file");
+}
+
+#[fastout(flush = line)]
+fn line() {
+    println!("first");
+    eprintln!("between");
+    println!("second");
+}
+
+#[fastout(capacity = 4)]
+fn small_capacity() {
+    println!("longer than capacity");
+    eprintln!("between");
+    println!("second");
+}
+
+#[fastout(flush = manual, capacity = 1)]
+fn manual() {
+    println!("first");
+    eprintln!("between");
+    println!("second");
+}
+
+#[proconio::main(multi, flush = line)]
+fn multi_line() {
+    proconio::input! {
+        n: u32,
+    }
+    println!("{}", n);
+    eprintln!("solved");
+}
+
+/// Runs this test in the mode with stdout and stderr redirected to the same file, and returns the
+/// merged output.
+fn run_merged(mode: &str, stdin: &str) -> String {
+    use std::io::Write;
+
+    let path = std::env::temp_dir().join(format!(
+        "proconio-fastout-options-{}-{}",
+        mode,
+        std::process::id()
+    ));
+    let file = File::create(&path).unwrap();
+    let mut child = Command::new(std::env::args().next().unwrap())
+        .arg(mode)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(file.try_clone().unwrap()))
+        .stderr(Stdio::from(file))
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+    let output = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    output
+}
+
+fn main() {
+    use assert_cli::Assert;
+    use std::env::args;
+
+    // relaunch the app to capture standard output
+    match args().nth(1).as_deref() {
+        None => {
+            let cmd = args().next().unwrap();
+            Assert::command(&[&*cmd, "nested"])
+                .stdout()
+                .is("outer\nouter again\n")
+                .and()
+                .stderr()
+                .is("to stderr\n")
+                .unwrap();
+            Assert::command(&[&*cmd, "to_file"])
+                .stdout()
+                .is("")
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(OUT_FILE).unwrap(), "to file\n");
+
+            assert_eq!(run_merged("line", ""), "first\nbetween\nsecond\n");
+            assert_eq!(
+                run_merged("small_capacity", ""),
+                "longer than capacity\nbetween\nsecond\n"
+            );
+            assert_eq!(run_merged("manual", ""), "between\nfirst\nsecond\n");
+            assert_eq!(
+                run_merged("multi_line", "2\n1\n2\n"),
+                "1\nsolved\n2\nsolved\n"
+            );
+        }
+        Some("nested") => nested(),
+        Some("to_file") => to_file(),
+        Some("line") => line(),
+        Some("small_capacity") => small_capacity(),
+        Some("manual") => manual(),
+        Some("multi_line") => multi_line(),
+        Some(mode) => panic!("unknown mode: {}", mode),
+    }
+}
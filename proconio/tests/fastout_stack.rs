@@ -0,0 +1,63 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use proconio::{fastout, input};
+
+// Deep enough to overflow the default stack of the main thread.
+fn depth(n: u64) -> u64 {
+    if n == 0 {
+        0
+    } else {
+        depth(n - 1) + 1
+    }
+}
+
+#[fastout(stack = "512MiB")]
+fn solve_fastout() -> u64 {
+    input! {
+        n: u64,
+    }
+
+    let d = depth(n);
+    println!("{}", d);
+    d
+}
+
+#[proconio::main(multi, stack = 512 * 1024 * 1024)]
+fn solve_multi() {
+    input! {
+        n: u64,
+    }
+
+    println!("{}", depth(n));
+}
+
+fn test_for(mode: &str, input: &str, expected_stdout: &str) {
+    use assert_cli::Assert;
+    use std::env::args;
+    Assert::command(&[&*args().next().unwrap(), mode])
+        .stdin(input)
+        .stdout()
+        .is(expected_stdout)
+        .and()
+        .stderr()
+        .is("")
+        .unwrap();
+}
+
+fn main() {
+    use std::env::args;
+    match args().nth(1).as_deref() {
+        None => {
+            test_for("fastout", "1000000\n", "1000000\n");
+            test_for("multi", "2\n1000000\n3\n", "1000000\n3\n");
+        }
+        Some("fastout") => assert_eq!(solve_fastout(), 1_000_000),
+        Some("multi") => solve_multi(),
+        Some(mode) => panic!("unknown mode: {}", mode),
+    }
+}
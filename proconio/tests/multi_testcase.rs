@@ -0,0 +1,77 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use proconio::input;
+
+#[proconio::main(multi)]
+fn solve_multi() {
+    input! {
+        n: usize,
+        a: [i64; n],
+    }
+
+    if n == 0 {
+        println!("empty");
+        return;
+    }
+
+    println!("{}", a.iter().sum::<i64>());
+}
+
+#[proconio::main(until_eof)]
+fn solve_until_eof() {
+    input! {
+        a: i64,
+        b: i64,
+    }
+
+    assert!(b != 0, "division by zero");
+    println!("{}", a / b);
+}
+
+fn test_for(mode: &str, input: &str, expected_stdout: &str) {
+    use assert_cli::Assert;
+    use std::env::args;
+    Assert::command(&[&*args().next().unwrap(), mode])
+        .stdin(input)
+        .stdout()
+        .is(expected_stdout)
+        .and()
+        .stderr()
+        .is("")
+        .unwrap();
+}
+
+fn test_panics(mode: &str, input: &str, expected_stderr: &str) {
+    use assert_cli::Assert;
+    use std::env::args;
+    Assert::command(&[&*args().next().unwrap(), mode])
+        .stdin(input)
+        .fails()
+        .and()
+        .stderr()
+        .contains(expected_stderr)
+        .unwrap();
+}
+
+fn main() {
+    use std::env::args;
+    match args().nth(1).as_deref() {
+        None => {
+            test_for("multi", "3\n2\n1 2\n0\n\n3\n1 2 3\n", "3\nempty\n6\n");
+            test_for("until_eof", "6 3\n10 5\n7 7\n", "2\n2\n1\n");
+            test_panics(
+                "until_eof",
+                "6 3\n10 0\n",
+                "panicked while solving test case #2 (1-indexed)",
+            );
+        }
+        Some("multi") => solve_multi(),
+        Some("until_eof") => solve_until_eof(),
+        Some(mode) => panic!("unknown mode: {}", mode),
+    }
+}
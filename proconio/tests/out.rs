@@ -0,0 +1,88 @@
+// Copyright 2019 statiolake <statiolake@gmail.com>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be copied, modified, or
+// distributed except according to those terms.
+
+use proconio::out::{print, println};
+use std::fmt;
+
+fn print_path(path: &[usize]) {
+    for v in path {
+        print!("{} ", v);
+    }
+    println!();
+}
+
+fn test_helper() {
+    println!("{}", 3);
+    print_path(&[1, 3, 2]);
+    let thread = std::thread::spawn(|| print_path(&[4, 5]));
+    thread.join().unwrap();
+}
+
+struct Logged(i32);
+
+impl fmt::Display for Logged {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        println!("formatting {}", self.0);
+        write!(f, "{}", self.0)
+    }
+}
+
+fn test_nested() {
+    // printing inside `Display` must not deadlock.
+    println!("{} {}", Logged(1), Logged(2));
+}
+
+fn test_exit() {
+    println!("before exit");
+    std::process::exit(0);
+}
+
+fn test_panic() {
+    println!("before panic");
+    panic!("boom");
+}
+
+fn main() {
+    use assert_cli::Assert;
+    use std::env::args;
+
+    // relaunch the app to capture standard output
+    match args().nth(1).as_deref() {
+        None => {
+            let cmd = args().next().unwrap();
+            Assert::command(&[&*cmd, "helper"])
+                .stdout()
+                .is("3\n1 3 2 \n4 5 \n")
+                .and()
+                .stderr()
+                .is("")
+                .unwrap();
+            Assert::command(&[&*cmd, "nested"])
+                .stdout()
+                .is("formatting 1\nformatting 2\n1 2\n")
+                .unwrap();
+            Assert::command(&[&*cmd, "exit"])
+                .stdout()
+                .is("before exit\n")
+                .unwrap();
+            Assert::command(&[&*cmd, "panic"])
+                .fails()
+                .and()
+                .stdout()
+                .is("before panic\n")
+                .and()
+                .stderr()
+                .contains("boom")
+                .unwrap();
+        }
+        Some("helper") => test_helper(),
+        Some("nested") => test_nested(),
+        Some("exit") => test_exit(),
+        Some("panic") => test_panic(),
+        Some(mode) => panic!("unknown mode: {}", mode),
+    }
+}
@@ -0,0 +1,10 @@
+use proconio::derive_readable;
+
+#[derive_readable]
+struct Case {
+    n: usize,
+    #[readable(len = n)]
+    a: [i64; 3],
+}
+
+fn main() {}
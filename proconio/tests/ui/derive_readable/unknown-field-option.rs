@@ -0,0 +1,10 @@
+use proconio::derive_readable;
+
+#[derive_readable]
+struct Case {
+    n: usize,
+    #[readable(length = n)]
+    a: Vec<i64>,
+}
+
+fn main() {}
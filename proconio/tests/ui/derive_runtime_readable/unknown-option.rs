@@ -0,0 +1,9 @@
+use proconio::derive_runtime_readable;
+
+#[derive_runtime_readable(params(n: usize), size = 3)]
+struct Graph {
+    #[readable(len = n)]
+    degrees: Vec<usize>,
+}
+
+fn main() {}
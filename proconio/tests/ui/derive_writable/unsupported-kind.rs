@@ -0,0 +1,10 @@
+use proconio::derive_writable;
+
+#[derive_writable]
+struct Case {
+    n: usize,
+    #[readable(kind = "[i64; n] as BTreeSet")]
+    a: std::collections::BTreeSet<i64>,
+}
+
+fn main() {}
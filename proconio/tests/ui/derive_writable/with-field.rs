@@ -0,0 +1,10 @@
+use proconio::derive_writable;
+
+#[derive_writable]
+struct Case {
+    n: usize,
+    #[readable(with = rows(n))]
+    a: Vec<Vec<char>>,
+}
+
+fn main() {}
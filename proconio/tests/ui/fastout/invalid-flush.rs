@@ -0,0 +1,4 @@
+use proconio::fastout;
+
+#[fastout(flush = always)]
+fn main() {}
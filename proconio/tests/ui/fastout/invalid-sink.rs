@@ -0,0 +1,4 @@
+use proconio::fastout;
+
+#[fastout(sink = stdin)]
+fn main() {}
@@ -0,0 +1,4 @@
+use proconio::fastout;
+
+#[fastout(stack = "512 megabytes")]
+fn main() {}
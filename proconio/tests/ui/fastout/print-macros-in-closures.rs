@@ -1,6 +0,0 @@
-use proconio::fastout;
-
-#[fastout]
-fn main() {
-    let _ = || println!("Hi");
-}
@@ -0,0 +1,4 @@
+use proconio::fastout;
+
+#[fastout(stak = "512MiB")]
+fn main() {}
@@ -0,0 +1,8 @@
+use proconio::fastout;
+
+#[fastout]
+fn main() {
+    use std::io::{stdout, Write};
+
+    writeln!(stdout(), "hello").unwrap();
+}
@@ -0,0 +1,10 @@
+use proconio::input;
+use proconio::source::auto::AutoSource;
+
+fn main() {
+    let source = AutoSource::from("1 2");
+    input! {
+        from source,
+        pair: Box<(i32, i32)>,
+    }
+}
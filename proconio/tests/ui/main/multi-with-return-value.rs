@@ -0,0 +1,6 @@
+#[proconio::main(multi)]
+fn solve() -> i32 {
+    42
+}
+
+fn main() {}
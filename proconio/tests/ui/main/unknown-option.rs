@@ -0,0 +1,4 @@
+#[proconio::main(multiple)]
+fn solve() {}
+
+fn main() {}